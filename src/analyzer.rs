@@ -1,19 +1,80 @@
 //! Deals entirely with schema analysis for the purpose of creating output structs + members
-use crate::{Container, MapType, Member, Output};
+use crate::{Constraints, Container, MapType, Member, Naming, Output, TimeType};
 use anyhow::{bail, Result};
 use heck::ToUpperCamelCase;
 use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
     JSONSchemaProps, JSONSchemaPropsOrArray, JSONSchemaPropsOrBool, JSON,
 };
+use serde::Deserialize;
 use std::collections::{BTreeMap, HashMap};
 
 const IGNORED_KEYS: [&str; 3] = ["metadata", "apiVersion", "kind"];
 
+/// The pattern kubernetes puts on resource.Quantity properties
+const QUANTITY_PATTERN: &str = r"^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$";
+
 #[derive(Default)]
 pub struct Config {
     pub no_condition: bool,
+    pub quantity: bool,
+    pub durations: bool,
+    pub formats: bool,
+    pub bytes: bool,
+    pub secrets: bool,
+    pub secret_names: Vec<glob::Pattern>,
+    pub narrow_integers: bool,
     pub map: MapType,
     pub relaxed: bool,
+    pub time: TimeType,
+    /// How nested containers are named
+    pub naming: Naming,
+    /// Maximum length of the names of nested containers
+    pub max_name_length: Option<usize>,
+    /// Overrides by the JSON path of a property, such as `spec.endpoints`
+    pub overrides: BTreeMap<String, Override>,
+}
+
+/// Overrides for the container or member generated for a property
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Override {
+    /// Name of the container
+    pub rename: Option<String>,
+    /// Rust type of the member, wrapped in an Option unless the member is required
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// Whether the member is required, regardless of the schema
+    pub required: Option<bool>,
+    /// Attributes for the container
+    pub attrs: Vec<String>,
+    /// Attributes for the member
+    pub member_attrs: Vec<String>,
+    /// Leave the container and everything below it out of the output
+    pub elide: bool,
+    /// External type used in place of the container, which is not generated
+    #[serde(rename = "use")]
+    pub use_: Option<String>,
+}
+
+impl Config {
+    fn override_for(&self, path: &str) -> Option<&Override> {
+        self.overrides.get(path)
+    }
+
+    /// Whether the analyzer should not recurse into the container for a property
+    fn skips(&self, path: &str) -> bool {
+        self.override_for(path)
+            .is_some_and(|o| o.elide || o.use_.is_some() || o.type_.is_some())
+    }
+
+    /// The type of the container for a property, unless it is renamed or replaced by an override
+    fn container_type(&self, stack: &str, path: &str, key: &str) -> String {
+        let o = self.override_for(&child_path(path, key));
+        if let Some(type_) = o.and_then(|o| o.use_.clone().or_else(|| o.rename.clone())) {
+            return type_;
+        }
+        format!("{}{}", stack, key.to_upper_camel_case())
+    }
 }
 
 /// Scan a schema for structs and members, and recurse to find all structs
@@ -21,8 +82,134 @@ pub struct Config {
 /// All found output structs will have its names prefixed by the kind it is for
 pub fn analyze(schema: JSONSchemaProps, kind: &str, cfg: Config) -> Result<Output> {
     let mut res = vec![];
-    analyze_(&schema, "", kind, 0, &mut res, &cfg)?;
-    Ok(Output(res))
+    analyze_(&schema, "", kind, "", 0, &mut res, &cfg)?;
+    let mut output = Output(res);
+    name_containers(&mut output, kind, &cfg);
+    Ok(output)
+}
+
+/// Scan the subschema at a JSON path such as `spec.endpoints` for structs and members
+///
+/// Containers are named and nested as when analyzing the whole schema,
+/// with the container at the path as the outermost container.
+pub fn analyze_path(schema: JSONSchemaProps, path: &str, kind: &str, cfg: Config) -> Result<Output> {
+    let mut node = &schema;
+    let mut stack = kind.to_string();
+    let mut current = String::new();
+    let mut parent = String::new();
+    let mut level = 0;
+    for key in path.split('.') {
+        // map values have the properties of maps of objects
+        let props = match &node.additional_properties {
+            Some(JSONSchemaPropsOrBool::Schema(s)) => s.properties.as_ref(),
+            _ => node.properties.as_ref(),
+        };
+        let Some(mut value) = props.and_then(|p| p.get(key)) else {
+            bail!("no property {} in the schema", child_path(&parent, key));
+        };
+        while let Some(JSONSchemaPropsOrArray::Schema(items)) = &value.items {
+            value = items; // containers in arrays are generated for the items
+        }
+        stack = cfg.container_type(&stack, &parent, key);
+        current = key.to_upper_camel_case();
+        parent = child_path(&parent, key);
+        level += 1;
+        node = value;
+    }
+    let mut res = vec![];
+    analyze_(node, &current, &stack, &parent, level, &mut res, &cfg)?;
+    if res.is_empty() {
+        bail!("no container is generated for {}", path);
+    }
+    let mut output = Output(res);
+    name_containers(&mut output, kind, &cfg);
+    Ok(output)
+}
+
+/// Rename nested containers following the naming strategy and the maximum name length
+///
+/// Top level containers and containers renamed by an override keep their names.
+/// New names are never the name of another container, also after trimming the `{kind}Spec` prefix.
+fn name_containers(output: &mut Output, kind: &str, cfg: &Config) {
+    if cfg.naming == Naming::Full && cfg.max_name_length.is_none() {
+        return;
+    }
+    let fixed = |c: &Container| c.level <= 1 || cfg.override_for(&c.path).is_some_and(|o| o.rename.is_some());
+    let segments: Vec<Vec<String>> = output
+        .0
+        .iter()
+        .map(|c| c.path.split('.').map(|k| k.to_upper_camel_case()).collect())
+        .collect();
+    let suffix = |segs: &[String], n: usize| format!("{}{}", kind, segs[segs.len() - n..].concat());
+    let unique = |segs: &[String], n: usize| {
+        segments
+            .iter()
+            .filter(|s| s.ends_with(&segs[segs.len() - n..]))
+            .count()
+            == 1
+    };
+    // names are printed without the spec prefix
+    let spec = format!("{}Spec", kind);
+    let trimmed = |name: &str| name.replace(&spec, kind);
+    let fits = |name: &String| cfg.max_name_length.is_none_or(|max| trimmed(name).len() <= max);
+    let mut taken: Vec<String> = output
+        .0
+        .iter()
+        .flat_map(|c| [c.name.clone(), trimmed(&c.name)])
+        .collect();
+
+    let mut renames = BTreeMap::new();
+    for (c, segs) in output.0.iter().zip(&segments) {
+        if fixed(c) {
+            continue;
+        }
+        let mut candidates = vec![];
+        match cfg.naming {
+            Naming::Full => {}
+            Naming::Suffix => {
+                candidates.extend(
+                    (1..segs.len())
+                        .filter(|n| unique(segs, *n))
+                        .map(|n| suffix(segs, n)),
+                );
+            }
+            Naming::Title => {
+                candidates.extend(
+                    c.title
+                        .iter()
+                        .map(|t| format!("{}{}", kind, t.to_upper_camel_case())),
+                );
+            }
+        }
+        if fits(&c.name) {
+            // the current name is reserved for this container
+            candidates.push(c.name.clone());
+        } else {
+            // the longest suffixes that fit before truncating
+            candidates.extend((1..segs.len()).rev().map(|n| suffix(segs, n)));
+        }
+        let name = candidates
+            .into_iter()
+            .find(|name| fits(name) && (trimmed(name) == trimmed(&c.name) || !taken.contains(name)))
+            .unwrap_or_else(|| {
+                // truncate the shortest suffix and number it
+                let base = trimmed(&suffix(segs, 1));
+                let max = cfg.max_name_length.unwrap_or(base.len());
+                (2..)
+                    .map(|i: usize| {
+                        let i = i.to_string();
+                        let len = max.saturating_sub(i.len());
+                        format!("{}{}", base.chars().take(len).collect::<String>(), i)
+                    })
+                    .find(|name| !taken.contains(name))
+                    .unwrap()
+            });
+        if name != c.name {
+            taken.push(name.clone());
+            renames.insert(c.name.clone(), name);
+        }
+    }
+    output.rename_containers(&renames);
 }
 
 /// Scan a schema for structs and members, and recurse to find all structs
@@ -30,12 +217,14 @@ pub fn analyze(schema: JSONSchemaProps, kind: &str, cfg: Config) -> Result<Outpu
 /// schema: root schema / sub schema
 /// current: current key name (or empty string for first call) - must capitalize first letter
 /// stack: stacked concat of kind + current_{n-1} + ... + current (used to create dedup names/types)
+/// path: dotted property keys from the root to the current schema (empty string for first call)
 /// level: recursion level (start at 0)
 /// results: multable list of generated structs (not deduplicated)
 fn analyze_(
     schema: &JSONSchemaProps,
     current: &str,
     stack: &str,
+    path: &str,
     level: u8,
     results: &mut Vec<Container>,
     cfg: &Config,
@@ -43,6 +232,12 @@ fn analyze_(
     let props = schema.properties.clone().unwrap_or_default();
     let mut array_recurse_level: HashMap<String, u8> = Default::default();
 
+    // plain enums are containers without properties
+    if let Some(en) = enum_values(schema) {
+        results.push(analyze_enum_properties(en, stack, path, level, schema)?);
+        return Ok(());
+    }
+
     // create a Container if we have a container type:
     //trace!("analyze_ with {} + {}", current, stack);
     if schema.type_.clone().unwrap_or_default() == "object" {
@@ -50,12 +245,31 @@ fn analyze_(
         // https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#validation
         if let Some(JSONSchemaPropsOrBool::Schema(s)) = schema.additional_properties.as_ref() {
             let dict_type = s.type_.clone().unwrap_or_default();
+            if dict_type == "array" || is_map(s) {
+                // map values are arrays or maps, whose values are named after this member
+                return analyze_(nested_values(s), current, stack, path, level, results, cfg);
+            }
             // object with additionalProperties == map
             if let Some(extra_props) = &s.properties {
                 // map values is an object with properties
                 debug!("Generating map struct for {} (under {})", current, stack);
-                let c = extract_container(extra_props, stack, &mut array_recurse_level, level, schema, cfg)?;
+                let mut c = extract_container(
+                    extra_props,
+                    stack,
+                    path,
+                    &mut array_recurse_level,
+                    level,
+                    schema,
+                    cfg,
+                )?;
+                // rules on the map itself belong to the parent member, the container is the map value
+                c.validations = s.x_kubernetes_validations.clone().unwrap_or_default();
                 results.push(c);
+            } else if let Some(en) = enum_values(s) {
+                // map values are enums
+                debug!("Generating map value enum for {} (under {})", current, stack);
+                results.push(analyze_enum_properties(en, stack, path, level, s)?);
+                return Ok(());
             } else if !dict_type.is_empty() {
                 warn!("not generating type {} - using {} map", current, dict_type);
                 return Ok(()); // no members here - it'll be inlined
@@ -68,7 +282,7 @@ fn analyze_(
                 warn!("not generating type {} - using map", current);
                 return Ok(());
             }
-            let c = extract_container(&props, stack, &mut array_recurse_level, level, schema, cfg)?;
+            let c = extract_container(&props, stack, path, &mut array_recurse_level, level, schema, cfg)?;
             results.push(c);
         }
     }
@@ -82,10 +296,10 @@ fn analyze_(
     // again; additionalProperties XOR properties
     let extras = if let Some(JSONSchemaPropsOrBool::Schema(s)) = schema.additional_properties.as_ref() {
         let extra_props = s.properties.clone().unwrap_or_default();
-        find_containers(&extra_props, stack, &mut array_recurse_level, level, schema, cfg)?
+        find_containers(&extra_props, stack, path, &mut array_recurse_level, level, cfg)?
     } else {
         // regular properties only
-        find_containers(&props, stack, &mut array_recurse_level, level, schema, cfg)?
+        find_containers(&props, stack, path, &mut array_recurse_level, level, cfg)?
     };
     results.extend(extras);
 
@@ -100,9 +314,9 @@ fn analyze_(
 fn find_containers(
     props: &BTreeMap<String, JSONSchemaProps>,
     stack: &str,
+    path: &str,
     array_recurse_level: &mut HashMap<String, u8>,
     level: u8,
-    schema: &JSONSchemaProps,
     cfg: &Config,
 ) -> Result<Vec<Container>> {
     //trace!("finding containers in: {}", serde_yaml::to_string(&props)?);
@@ -113,34 +327,25 @@ fn find_containers(
             continue;
         }
         let next_key = key.to_upper_camel_case();
-        let next_stack = format!("{}{}", stack, next_key);
+        let next_path = child_path(path, key);
+        if cfg.skips(&next_path) {
+            debug!("not recursing into overridden {}", next_path);
+            continue;
+        }
+        let next_stack = cfg.container_type(stack, path, key);
         let value_type = value.type_.clone().unwrap_or_default();
         match value_type.as_ref() {
             "object" => {
-                // objects, maps
-                let mut handled_inner = false;
-                if let Some(JSONSchemaPropsOrBool::Schema(s)) = &value.additional_properties {
-                    let dict_type = s.type_.clone().unwrap_or_default();
-                    if dict_type == "array" {
-                        // unpack the inner object from the array wrap
-                        if let Some(JSONSchemaPropsOrArray::Schema(items)) = &s.as_ref().items {
-                            debug!("..recursing into object member {}", key);
-                            analyze_(items, &next_key, &next_stack, level + 1, &mut results, cfg)?;
-                            handled_inner = true;
-                        }
-                    }
-                    // TODO: not sure if these nested recurses are necessary - cluster test case does not have enough data
-                    //if let Some(extra_props) = &s.properties {
-                    //    for (_key, value) in extra_props {
-                    //        debug!("..nested recurse into {} {} - key: {}", next_key, next_stack, _key);
-                    //        analyze_(value.clone(), &next_key, &next_stack, level +1, results)?;
-                    //    }
-                    //}
-                }
-                if !handled_inner {
-                    // normal object recurse
-                    analyze_(value, &next_key, &next_stack, level + 1, &mut results, cfg)?;
-                }
+                // objects, maps (which unpack arrays and maps in their values)
+                analyze_(
+                    value,
+                    &next_key,
+                    &next_stack,
+                    &next_path,
+                    level + 1,
+                    &mut results,
+                    cfg,
+                )?;
             }
             "array" => {
                 if let Some(recurse) = array_recurse_level.get(key).cloned() {
@@ -159,23 +364,45 @@ fn find_containers(
                             bail!("could not recurse into vec");
                         }
                     }
-                    analyze_(&inner, &next_key, &next_stack, level + 1, &mut results, cfg)?;
+                    analyze_(
+                        &inner,
+                        &next_key,
+                        &next_stack,
+                        &next_path,
+                        level + 1,
+                        &mut results,
+                        cfg,
+                    )?;
                 }
             }
             "" => {
-                if value.x_kubernetes_int_or_string.is_some() {
+                if enum_values(value).is_some() {
+                    analyze_(
+                        value,
+                        &next_key,
+                        &next_stack,
+                        &next_path,
+                        level + 1,
+                        &mut results,
+                        cfg,
+                    )?;
+                } else if value.x_kubernetes_int_or_string.is_some() {
                     debug!("..not recursing into IntOrString {}", key)
                 } else {
                     debug!("..not recursing into unknown empty type {}", key)
                 }
             }
             x => {
-                if let Some(en) = &value.enum_ {
-                    // plain enums do not need to recurse, can collect it here
-                    // ....although this makes it impossible for us to handle enums at the top level
-                    // TODO: move this to the top level
-                    let new_result = analyze_enum_properties(en, &next_stack, level, schema)?;
-                    results.push(new_result);
+                if enum_values(value).is_some() {
+                    analyze_(
+                        value,
+                        &next_key,
+                        &next_stack,
+                        &next_path,
+                        level + 1,
+                        &mut results,
+                        cfg,
+                    )?;
                 } else {
                     debug!("..not recursing into {} ('{}' is not a container)", key, x)
                 }
@@ -189,6 +416,7 @@ fn find_containers(
 fn analyze_enum_properties(
     items: &Vec<JSON>,
     stack: &str,
+    path: &str,
     level: u8,
     schema: &JSONSchemaProps,
 ) -> Result<Container, anyhow::Error> {
@@ -200,31 +428,42 @@ fn analyze_enum_properties(
         let name = match &en.0 {
             serde_json::Value::String(name) => name.to_string(),
             serde_json::Value::Number(val) => {
-                if !val.is_u64() {
-                    bail!("enum member cannot have signed/floating discriminants");
-                }
-                val.to_string()
+                // numbers are not identifiers, give them a prefix instead of escaping them
+                let val = val.to_string().replace('-', "Minus").replace('.', "Point");
+                format!("Value{val}")
             }
-            _ => bail!("not handling non-string/int enum outside oneOf block"),
+            serde_json::Value::Bool(val) => val.to_string().to_upper_camel_case(),
+            _ => bail!("not handling null/array/object enum values outside oneOf block"),
         };
         let rust_type = "".to_string();
         // Create member and wrap types correctly
         let member_doc = None;
         debug!("with enum member {}", name);
+        // the schema default becomes the default variant
+        let mut extra_annot = vec![];
+        if schema.default.as_ref().is_some_and(|d| d.0 == en.0) {
+            extra_annot.push("#[default]".to_string());
+        }
         members.push(Member {
             type_: rust_type,
             name: name.to_string(),
             serde_annot: vec![],
-            extra_annot: vec![],
+            extra_annot,
             docs: member_doc,
+            constraints: Default::default(),
+            extensions: Default::default(),
+            value: Some(en.0.clone()),
         })
     }
     Ok(Container {
         name: stack.to_string(),
+        path: path.to_string(),
         members,
         level,
         docs: schema.description.clone(),
+        title: schema.title.clone(),
         is_enum: true,
+        validations: vec![],
     })
 }
 
@@ -232,6 +471,7 @@ fn analyze_enum_properties(
 fn extract_container(
     props: &BTreeMap<String, JSONSchemaProps>,
     stack: &str,
+    path: &str,
     array_recurse_level: &mut HashMap<String, u8>,
     level: u8,
     schema: &JSONSchemaProps,
@@ -246,7 +486,7 @@ fn extract_container(
             "object" => {
                 let mut dict_key = None;
                 if let Some(additional) = &value.additional_properties {
-                    dict_key = resolve_additional_properties(additional, stack, key, value)?;
+                    dict_key = resolve_additional_properties(additional, stack, path, key, cfg)?;
                 } else if value.properties.is_none()
                     && value.x_kubernetes_preserve_unknown_fields.unwrap_or(false)
                 {
@@ -255,24 +495,26 @@ fn extract_container(
                 if let Some(dict) = dict_key {
                     format!("{}<String, {}>", cfg.map.name(), dict)
                 } else {
-                    format!("{}{}", stack, key.to_upper_camel_case())
+                    cfg.container_type(stack, path, key)
                 }
             }
-            "string" => {
-                if let Some(_en) = &value.enum_ {
-                    trace!("got enum string: {}", serde_json::to_string(&schema).unwrap());
-                    format!("{}{}", stack, key.to_upper_camel_case())
-                } else {
-                    "String".to_string()
-                }
+            "string" | "boolean" | "number" | "integer" | "" if enum_values(value).is_some() => {
+                trace!(
+                    "got enum {}: {}",
+                    value_type,
+                    serde_json::to_string(&schema).unwrap()
+                );
+                cfg.container_type(stack, path, key)
             }
+            "string" => extract_string_type(value, key, cfg)?,
             "boolean" => "bool".to_string(),
-            "date" => extract_date_type(value)?,
+            "date" => extract_date_type(value, cfg)?,
             "number" => extract_number_type(value)?,
-            "integer" => extract_integer_type(value)?,
+            "integer" => extract_integer_type(value, cfg)?,
             "array" => {
                 // recurse through repeated arrays until we find a concrete type (keep track of how deep we went)
-                let (mut array_type, recurse_level) = array_recurse_for_type(value, stack, key, 1, cfg)?;
+                let (mut array_type, recurse_level) =
+                    array_recurse_for_type(value, stack, path, key, 1, cfg)?;
                 trace!("got array {} for {} in level {}", array_type, key, recurse_level);
                 if !cfg.no_condition && key == "conditions" && is_conditions(value) {
                     array_type = "Vec<Condition>".into();
@@ -284,7 +526,7 @@ fn extract_container(
             "" => {
                 let map_type = cfg.map.name();
                 if value.x_kubernetes_int_or_string.is_some() {
-                    "IntOrString".into()
+                    extract_int_or_string_type(value, cfg)
                 } else if value.x_kubernetes_preserve_unknown_fields == Some(true) {
                     "serde_json::Value".into()
                 } else if cfg.relaxed {
@@ -299,14 +541,31 @@ fn extract_container(
 
         // Create member and wrap types correctly
         let member_doc = value.description.clone();
-        if reqs.contains(key) {
+        let constraints = extract_constraints(value, &child_path(path, key));
+        let mut extensions = extract_extensions(value)?;
+        if rust_type == "Quantity" {
+            // Quantity only deserializes from strings, so its schema cannot accept integers
+            extensions.remove("x-kubernetes-int-or-string");
+        }
+        let member_override = cfg.override_for(&child_path(path, key));
+        let rust_type = member_override.and_then(|o| o.type_.clone()).unwrap_or(rust_type);
+        let extra_annot = member_override
+            .map(|o| o.member_attrs.clone())
+            .unwrap_or_default();
+        let required = member_override
+            .and_then(|o| o.required)
+            .unwrap_or_else(|| reqs.contains(key));
+        if required {
             debug!("with required member {} of type {}", key, &rust_type);
             members.push(Member {
                 type_: rust_type,
                 name: key.to_string(),
                 serde_annot: vec![],
-                extra_annot: vec![],
+                extra_annot,
                 docs: member_doc,
+                constraints,
+                extensions,
+                value: None,
             })
         } else {
             // option wrapping needed if not required
@@ -318,8 +577,11 @@ fn extract_container(
                     "default".into(),
                     "skip_serializing_if = \"Option::is_none\"".into(),
                 ],
-                extra_annot: vec![],
+                extra_annot,
                 docs: member_doc,
+                constraints,
+                extensions,
+                value: None,
             })
             // TODO: must capture `default` key here instead of blindly using serde default
             // this will require us storing default properties for the member in above loop
@@ -329,18 +591,22 @@ fn extract_container(
     }
     Ok(Container {
         name: stack.to_string(),
+        path: path.to_string(),
         members,
         level,
         docs: schema.description.clone(),
+        title: schema.title.clone(),
         is_enum: false,
+        validations: schema.x_kubernetes_validations.clone().unwrap_or_default(),
     })
 }
 
 fn resolve_additional_properties(
     additional: &JSONSchemaPropsOrBool,
     stack: &str,
+    path: &str,
     key: &str,
-    value: &JSONSchemaProps,
+    cfg: &Config,
 ) -> Result<Option<String>, anyhow::Error> {
     debug!("got additional: {}", serde_json::to_string(&additional)?);
     let JSONSchemaPropsOrBool::Schema(s) = additional else {
@@ -349,54 +615,34 @@ fn resolve_additional_properties(
 
     // This case is for maps. It is generally String -> Something, depending on the type key:
     let dict_type = s.type_.clone().unwrap_or_default();
+    if enum_values(s).is_some() {
+        return Ok(Some(cfg.container_type(stack, path, key)));
+    }
     let dict_key = match dict_type.as_ref() {
-        "string" => Some("String".into()),
+        "string" => Some(extract_string_type(s, key, cfg)?),
         // We are not 100% sure the array and object subcases here are correct but they pass tests atm.
         // authoratative, but more detailed sources than crd validation docs below are welcome
         // https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#validation
-        "array" => {
-            let mut simple_inner = None;
-            if let Some(JSONSchemaPropsOrArray::Schema(ix)) = &s.items {
-                simple_inner = ix.type_.clone();
-                debug!("additional simple inner  type: {:?}", simple_inner);
-            }
-            // Simple case: additionalProperties contain: {items: {type: K}}
-            // Then it's a simple map (service_monitor_params) - but key is useless
-            match simple_inner.as_deref() {
-                Some("string") => Some("String".into()),
-                Some("integer") => Some(extract_integer_type(s)?),
-                Some("date") => Some(extract_date_type(value)?),
-                Some("") => {
-                    if s.x_kubernetes_int_or_string.is_some() {
-                        Some("IntOrString".into())
-                    } else {
-                        bail!("unknown inner empty dict type for {}", key)
-                    }
-                }
-                // can probably cover the regulars here as well
-
-                // Harder case: inline structs under items (agent test with `validationInfo`)
-                // key becomes the struct
-                Some("object") => Some(format!("{}{}", stack, key.to_upper_camel_case())),
-                None => Some(format!("{}{}", stack, key.to_upper_camel_case())),
-
-                // leftovers, array of arrays?... need a better way to recurse probably
-                Some(x) => bail!("unknown inner empty dict type {} for {}", x, key),
-            }
+        // map of arrays (service_monitor_params), with structs under items in the agent test
+        "array" => Some(array_recurse_for_type(s, stack, path, key, 1, cfg)?.0),
+        "object" if is_map(s) => {
+            let values = s.additional_properties.as_ref().unwrap();
+            resolve_additional_properties(values, stack, path, key, cfg)?
+                .map(|values| format!("{}<String, {}>", cfg.map.name(), values))
         }
         "object" => {
             // cluster test with `failureDomains` uses this spec format
-            Some(format!("{}{}", stack, key.to_upper_camel_case()))
+            Some(cfg.container_type(stack, path, key))
         }
         "" => {
             if s.x_kubernetes_int_or_string.is_some() {
-                Some("IntOrString".into())
+                Some(extract_int_or_string_type(s, cfg))
             } else {
                 bail!("unknown empty dict type for {}", key)
             }
         }
         "boolean" => Some("bool".to_string()),
-        "integer" => Some(extract_integer_type(s)?),
+        "integer" => Some(extract_integer_type(s, cfg)?),
         // think the type we get is the value type
         x => Some(x.to_upper_camel_case()), // best guess
     };
@@ -409,6 +655,7 @@ fn resolve_additional_properties(
 fn array_recurse_for_type(
     value: &JSONSchemaProps,
     stack: &str,
+    path: &str,
     key: &str,
     level: u8,
     cfg: &Config,
@@ -420,33 +667,40 @@ fn array_recurse_for_type(
                     let map_type = cfg.map.name();
                     return Ok((format!("Vec<{}<String, serde_json::Value>>", map_type), level));
                 }
+                if enum_values(s).is_some() {
+                    return Ok((format!("Vec<{}>", cfg.container_type(stack, path, key)), level));
+                }
                 let inner_array_type = s.type_.clone().unwrap_or_default();
-                return match inner_array_type.as_ref() {
+                match inner_array_type.as_ref() {
                     "object" => {
                         // Same logic as in `extract_container` to simplify types to maps.
                         let mut dict_value = None;
                         if let Some(additional) = &s.additional_properties {
-                            dict_value = resolve_additional_properties(additional, stack, key, s)?;
+                            dict_value = resolve_additional_properties(additional, stack, path, key, cfg)?;
                         }
 
                         let vec_value = if let Some(dict_value) = dict_value {
                             let map_type = cfg.map.name();
                             format!("{map_type}<String, {dict_value}>")
                         } else {
-                            let structsuffix = key.to_upper_camel_case();
-                            format!("{stack}{structsuffix}")
+                            cfg.container_type(stack, path, key)
                         };
 
                         Ok((format!("Vec<{}>", vec_value), level))
                     }
-                    "string" => Ok(("Vec<String>".into(), level)),
+                    "string" => Ok((format!("Vec<{}>", extract_string_type(s, key, cfg)?), level)),
                     "boolean" => Ok(("Vec<bool>".into(), level)),
-                    "date" => Ok((format!("Vec<{}>", extract_date_type(value)?), level)),
-                    "number" => Ok((format!("Vec<{}>", extract_number_type(value)?), level)),
-                    "integer" => Ok((format!("Vec<{}>", extract_integer_type(value)?), level)),
+                    "date" => Ok((format!("Vec<{}>", extract_date_type(s, cfg)?), level)),
+                    "number" => Ok((format!("Vec<{}>", extract_number_type(s)?), level)),
+                    "integer" => Ok((format!("Vec<{}>", extract_integer_type(s, cfg)?), level)),
+                    "" if s.x_kubernetes_int_or_string.is_some() => {
+                        Ok((format!("Vec<{}>", extract_int_or_string_type(s, cfg)), level))
+                    }
                     "array" => {
                         if s.items.is_some() {
-                            Ok(array_recurse_for_type(s, stack, key, level + 1, cfg)?)
+                            let (array_type, recurse_level) =
+                                array_recurse_for_type(s, stack, path, key, level + 1, cfg)?;
+                            Ok((format!("Vec<{}>", array_type), recurse_level))
                         } else if cfg.relaxed {
                             warn!("Empty inner array in: {} key: {}", stack, key);
                             let map_type = cfg.map.name();
@@ -458,7 +712,7 @@ fn array_recurse_for_type(
                     unknown => {
                         bail!("unsupported recursive array type \"{unknown}\" for {key}")
                     }
-                };
+                }
             }
             // maybe fallback to serde_json::Value
             _ => bail!("only support single schema in array {}", key),
@@ -470,6 +724,44 @@ fn array_recurse_for_type(
 
 // ----------------------------------------------------------------------------
 // helpers
+
+/// The JSON path of a property below the schema at a path
+pub(crate) fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+/// The values of a plain enum, i.e. a non-object schema with an enum constraint
+fn enum_values(value: &JSONSchemaProps) -> Option<&Vec<JSON>> {
+    value
+        .enum_
+        .as_ref()
+        .filter(|_| value.type_.as_deref() != Some("object"))
+}
+
+/// Whether a schema is a map, i.e. an object with a schema for its values rather than properties
+fn is_map(value: &JSONSchemaProps) -> bool {
+    value.properties.is_none()
+        && matches!(
+            value.additional_properties,
+            Some(JSONSchemaPropsOrBool::Schema(_))
+        )
+}
+
+/// The schema of the values in nested arrays and maps, such as `T` for `Vec<BTreeMap<String, T>>`
+fn nested_values(value: &JSONSchemaProps) -> &JSONSchemaProps {
+    match (&value.items, &value.additional_properties) {
+        (Some(JSONSchemaPropsOrArray::Schema(items)), _) if value.type_.as_deref() == Some("array") => {
+            nested_values(items)
+        }
+        (_, Some(JSONSchemaPropsOrBool::Schema(values))) if is_map(value) => nested_values(values),
+        _ => value,
+    }
+}
+
 fn is_conditions(value: &JSONSchemaProps) -> bool {
     if let Some(JSONSchemaPropsOrArray::Schema(props)) = &value.items {
         if let Some(p) = &props.properties {
@@ -486,14 +778,149 @@ fn is_conditions(value: &JSONSchemaProps) -> bool {
     false
 }
 
-fn extract_date_type(value: &JSONSchemaProps) -> Result<String> {
+// plain schema constraints that are not represented by the rust type
+fn extract_constraints(value: &JSONSchemaProps, path: &str) -> Constraints {
+    // patterns are ECMA-262, so lookarounds or backreferences cannot be checked with the regex crate
+    let pattern = value.pattern.clone().filter(|p| match regex::Regex::new(p) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("not checking the pattern of {path} as it is not supported: {e}");
+            false
+        }
+    });
+    // items of arrays or values of maps, unless they are structs with constraints on their own members
+    let nested = nested_values(value);
+    let elements = if std::ptr::eq(nested, value) || nested.properties.is_some() {
+        None
+    } else {
+        Some(Box::new(extract_constraints(nested, path))).filter(|c| !c.is_empty())
+    };
+    Constraints {
+        pattern,
+        minimum: value.minimum,
+        maximum: value.maximum,
+        exclusive_minimum: value.exclusive_minimum.unwrap_or(false),
+        exclusive_maximum: value.exclusive_maximum.unwrap_or(false),
+        min_length: value.min_length,
+        max_length: value.max_length,
+        min_items: value.min_items,
+        max_items: value.max_items,
+        unique_items: value.unique_items.unwrap_or(false),
+        min_properties: value.min_properties,
+        max_properties: value.max_properties,
+        // objects with properties are structs, where required is expressed through Option
+        required: if value.properties.is_none() {
+            value.required.clone().unwrap_or_default()
+        } else {
+            vec![]
+        },
+        elements,
+    }
+}
+
+// kubernetes extensions that schemars cannot know about when deriving the schema for a member
+fn extract_extensions(value: &JSONSchemaProps) -> Result<BTreeMap<String, serde_json::Value>> {
+    let mut extensions = BTreeMap::new();
+    if value.x_kubernetes_preserve_unknown_fields == Some(true) {
+        extensions.insert("x-kubernetes-preserve-unknown-fields".into(), true.into());
+    }
+    if value.x_kubernetes_int_or_string == Some(true) {
+        extensions.insert("x-kubernetes-int-or-string".into(), true.into());
+    }
+    if let Some(list_type) = &value.x_kubernetes_list_type {
+        extensions.insert("x-kubernetes-list-type".into(), list_type.clone().into());
+    }
+    if let Some(keys) = &value.x_kubernetes_list_map_keys {
+        extensions.insert("x-kubernetes-list-map-keys".into(), keys.clone().into());
+    }
+    if let Some(map_type) = &value.x_kubernetes_map_type {
+        extensions.insert("x-kubernetes-map-type".into(), map_type.clone().into());
+    }
+    if let Some(validations) = &value.x_kubernetes_validations {
+        extensions.insert(
+            "x-kubernetes-validations".into(),
+            serde_json::to_value(validations)?,
+        );
+    }
+    Ok(extensions)
+}
+
+fn is_quantity(value: &JSONSchemaProps, cfg: &Config) -> bool {
+    cfg.quantity && value.pattern.as_deref() == Some(QUANTITY_PATTERN)
+}
+
+/// Whether a string property holds a duration such as "1h30m" or "250ms"
+///
+/// Detected from the duration format, or from patterns matching go (or prometheus) duration units.
+fn is_duration(value: &JSONSchemaProps) -> bool {
+    if value.format.as_deref() == Some("duration") {
+        return true;
+    }
+    value.pattern.as_deref().is_some_and(|p| {
+        // units are matched on their own (e.g. `(h|m|s|ms)`), or as suffixes of digit groups (e.g. `([0-9]+)h`)
+        let units = ["h", "m", "s", "ms"];
+        let alternation = p.contains("h|m|s|ms") || p.contains("ms|s|m|h");
+        let suffixed = units.iter().all(|u| p.contains(&format!("){u})")));
+        p.contains("[0-9]") && (alternation || suffixed)
+    })
+}
+
+/// Whether a string property is a password, or has a name matching one of the secret name patterns
+fn is_secret(value: &JSONSchemaProps, key: &str, cfg: &Config) -> bool {
+    let opts = glob::MatchOptions {
+        case_sensitive: false,
+        ..Default::default()
+    };
+    (cfg.secrets && value.format.as_deref() == Some("password"))
+        || cfg.secret_names.iter().any(|p| p.matches_with(key, opts))
+}
+
+fn extract_int_or_string_type(value: &JSONSchemaProps, cfg: &Config) -> String {
+    if is_quantity(value, cfg) {
+        "Quantity".to_string()
+    } else {
+        "IntOrString".to_string()
+    }
+}
+
+fn extract_string_type(value: &JSONSchemaProps, key: &str, cfg: &Config) -> Result<String> {
+    if is_secret(value, key, cfg) {
+        return Ok("RedactedString".to_string());
+    }
+    if is_quantity(value, cfg) {
+        return Ok("Quantity".to_string());
+    }
+    if cfg.durations && is_duration(value) {
+        return Ok("GoDuration".to_string());
+    }
+    Ok(match value.format.as_deref() {
+        Some("date") | Some("date-time") => extract_date_type(value, cfg)?,
+        Some("int-or-string") => extract_int_or_string_type(value, cfg),
+        // base64 encoded data, which ByteString (de)serializes transparently
+        Some("byte") if cfg.bytes => "ByteString".to_string(),
+        Some(f) if cfg.formats => extract_format_type(f).to_string(),
+        _ => "String".to_string(),
+    })
+}
+
+fn extract_format_type(format: &str) -> &'static str {
+    match format {
+        "uuid" => "Uuid",
+        "uri" => "Url",
+        "ipv4" => "Ipv4Addr",
+        "ipv6" => "Ipv6Addr",
+        "email" => "Email",
+        "hostname" => "Hostname",
+        _ => "String",
+    }
+}
+
+fn extract_date_type(value: &JSONSchemaProps, cfg: &Config) -> Result<String> {
     Ok(if let Some(f) = &value.format {
-        // NB: these need chrono feature on serde
+        // NB: these need serde support in the crate providing the time type
         match f.as_ref() {
-            // Not sure if the first actually works properly..
-            // might need a Date<Utc> but chrono docs advocated for NaiveDate
-            "date" => "NaiveDate".to_string(),
-            "date-time" => "DateTime<Utc>".to_string(),
+            "date" => cfg.time.date().to_string(),
+            "date-time" => cfg.time.date_time().to_string(),
             x => {
                 bail!("unknown date {}", x);
             }
@@ -504,7 +931,6 @@ fn extract_date_type(value: &JSONSchemaProps) -> Result<String> {
 }
 
 fn extract_number_type(value: &JSONSchemaProps) -> Result<String> {
-    // TODO: byte / password here?
     Ok(if let Some(f) = &value.format {
         match f.as_ref() {
             "float" => "f32".to_string(),
@@ -516,10 +942,49 @@ fn extract_number_type(value: &JSONSchemaProps) -> Result<String> {
     })
 }
 
-fn extract_integer_type(value: &JSONSchemaProps) -> Result<String> {
+fn extract_integer_type(value: &JSONSchemaProps, cfg: &Config) -> Result<String> {
     // Think kubernetes go types just do signed ints, but set a minimum to zero..
     // rust will set uint, so emitting that when possible
-    Ok(if let Some(f) = &value.format {
+    let type_ = extract_integer_format(value);
+    if cfg.narrow_integers {
+        return Ok(narrow_integer_type(value, &type_));
+    }
+    Ok(type_)
+}
+
+/// An unsigned type for a signed integer with a non-negative minimum, narrowed by its maximum
+///
+/// The width of the format is kept as an upper bound, so an int32 is at most a u32.
+fn narrow_integer_type(value: &JSONSchemaProps, type_: &str) -> String {
+    let Some(bits) = type_.strip_prefix('i').and_then(|b| b.parse::<u32>().ok()) else {
+        return type_.to_string(); // already unsigned
+    };
+    let minimum = value.minimum.map(|min| {
+        if value.exclusive_minimum == Some(true) {
+            min.floor() + 1.0
+        } else {
+            min.ceil()
+        }
+    });
+    if minimum.is_none_or(|min| min < 0.0) {
+        return type_.to_string();
+    }
+    let maximum = value.maximum.map(|max| {
+        if value.exclusive_maximum == Some(true) {
+            max.ceil() - 1.0
+        } else {
+            max.floor()
+        }
+    });
+    [16, 32, 64]
+        .into_iter()
+        .filter(|&b| b < bits)
+        .find(|&b| maximum.is_some_and(|max| max <= 2f64.powi(b as i32) - 1.0))
+        .map_or_else(|| format!("u{bits}"), |b| format!("u{b}"))
+}
+
+fn extract_integer_format(value: &JSONSchemaProps) -> String {
+    if let Some(f) = &value.format {
         match f.as_ref() {
             "int8" => "i8".to_string(),
             "int16" => "i16".to_string(),
@@ -535,13 +1000,14 @@ fn extract_integer_type(value: &JSONSchemaProps) -> Result<String> {
         }
     } else {
         "i64".to_string()
-    })
+    }
 }
 
 // unit tests particular schema patterns
 #[cfg(test)]
 mod test {
-    use super::{analyze, Config as Cfg};
+    use super::{analyze, analyze_path, Config as Cfg, Naming, Override};
+    use crate::TimeType;
     use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::JSONSchemaProps;
 
     use std::sync::Once;
@@ -595,7 +1061,7 @@ mod test {
         // should have a member with a key to the map:
         let map = &root.members[0];
         assert_eq!(map.name, "validationsInfo");
-        assert_eq!(map.type_, "Option<BTreeMap<String, AgentValidationsInfo>>");
+        assert_eq!(map.type_, "Option<BTreeMap<String, Vec<AgentValidationsInfo>>>");
         // should have a separate struct
         let other = &structs[1];
         assert_eq!(other.name, "AgentValidationsInfo");
@@ -729,134 +1195,806 @@ type: object
     }
 
     #[test]
-    fn boolean_in_additionals() {
-        // as found in argo-app
+    fn kubernetes_extensions() {
         init();
         let schema_str = r#"
             properties:
-              options:
-                additionalProperties:
-                  type: boolean
+              config:
                 type: object
-              patch:
+                x-kubernetes-preserve-unknown-fields: true
+              hosts:
+                items:
+                  properties:
+                    name:
+                      type: string
+                  required:
+                  - name
+                  type: object
+                type: array
+                x-kubernetes-list-map-keys:
+                - name
+                x-kubernetes-list-type: map
+                x-kubernetes-validations:
+                - message: hosts must be unique
+                  rule: self.all(x, self.exists_one(y, x.name == y.name))
+              name:
                 type: string
             type: object
 "#;
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
-        let structs = analyze(schema, "Options", Cfg::default()).unwrap().0;
-        println!("got {:?}", structs);
+        let structs = analyze(schema, "Gateway", Cfg::default()).unwrap().0;
         let root = &structs[0];
-        assert_eq!(root.name, "Options");
-        assert_eq!(root.level, 0);
-        assert_eq!(&root.members[0].name, "options");
-        assert_eq!(&root.members[0].type_, "Option<BTreeMap<String, bool>>");
+        let config = &root.members[0];
+        assert_eq!(config.type_, "Option<BTreeMap<String, serde_json::Value>>");
+        assert_eq!(
+            config.extensions["x-kubernetes-preserve-unknown-fields"],
+            serde_json::json!(true)
+        );
+        let hosts = &root.members[1];
+        assert_eq!(hosts.type_, "Option<Vec<GatewayHosts>>");
+        assert_eq!(
+            hosts.extensions["x-kubernetes-list-type"],
+            serde_json::json!("map")
+        );
+        assert_eq!(
+            hosts.extensions["x-kubernetes-list-map-keys"],
+            serde_json::json!(["name"])
+        );
+        assert_eq!(
+            hosts.extensions["x-kubernetes-validations"],
+            serde_json::json!([{
+                "message": "hosts must be unique",
+                "rule": "self.all(x, self.exists_one(y, x.name == y.name))"
+            }])
+        );
+        assert!(root.members[2].extensions.is_empty());
     }
 
     #[test]
-    fn enum_string() {
+    fn validations_on_containers_and_members() {
         init();
         let schema_str = r#"
-      properties:
-        operator:
-          enum:
-          - In
-          - NotIn
-          - Exists
-          - DoesNotExist
-          type: string
-      required:
-      - operator
-      type: object
+            properties:
+              ports:
+                items:
+                  properties:
+                    port:
+                      type: integer
+                  type: object
+                  x-kubernetes-validations:
+                  - rule: self.port > 0
+                type: array
+                x-kubernetes-validations:
+                - message: too many ports
+                  rule: size(self) < 5
+            type: object
+            x-kubernetes-validations:
+            - rule: has(self.ports)
 "#;
-
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
-        let structs = analyze(schema, "MatchExpressions", Cfg::default()).unwrap().0;
-        println!("got {:?}", structs);
+        let structs = analyze(schema, "Widget", Cfg::default()).unwrap().0;
         let root = &structs[0];
-        assert_eq!(root.name, "MatchExpressions");
-        assert_eq!(root.level, 0);
-        assert_eq!(&root.members[0].name, "operator");
-        assert_eq!(&root.members[0].type_, "MatchExpressionsOperator");
-
-        // operator member
-        let op = &structs[1];
-        assert!(op.is_enum);
-        assert_eq!(op.name, "MatchExpressionsOperator");
-
-        // should have enum members:
-        assert_eq!(&op.members[0].name, "In");
-        assert_eq!(&op.members[0].type_, "");
-        assert_eq!(&op.members[1].name, "NotIn");
-        assert_eq!(&op.members[1].type_, "");
-        assert_eq!(&op.members[2].name, "Exists");
-        assert_eq!(&op.members[2].type_, "");
-        assert_eq!(&op.members[3].name, "DoesNotExist");
-        assert_eq!(&op.members[3].type_, "");
+        assert_eq!(root.validations[0].rule, "has(self.ports)");
+        let ports = &root.members[0].validations();
+        assert_eq!(ports[0].rule, "size(self) < 5");
+        assert_eq!(ports[0].message.as_deref(), Some("too many ports"));
+        let item = &structs[1];
+        assert_eq!(item.name, "WidgetPorts");
+        assert_eq!(item.validations[0].rule, "self.port > 0");
+        assert!(item.members[0].validations().is_empty());
     }
 
     #[test]
-    fn enum_string_within_container() {
+    fn constraints() {
         init();
         let schema_str = r#"
-      description: Endpoint
-      properties:
-        relabelings:
-          items:
             properties:
-              action:
-                default: replace
-                enum:
-                - replace
-                - keep
-                - drop
-                - hashmod
-                - labelmap
-                - labeldrop
-                - labelkeep
+              name:
+                maxLength: 63
+                pattern: ^[a-z]+$
                 type: string
-              modulus:
-                format: int64
+              replicas:
+                exclusiveMaximum: true
+                maximum: 10
+                minimum: 0
                 type: integer
+              hosts:
+                items:
+                  pattern: ^[a-z.]+$
+                  type: string
+                minItems: 1
+                type: array
+                uniqueItems: true
+              selector:
+                additionalProperties:
+                  type: string
+                maxProperties: 5
+                required:
+                - app
+                type: object
             type: object
-          type: array
-      type: object
-        "#;
-
+"#;
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
-        let structs = analyze(schema, "Endpoint", Cfg::default()).unwrap().0;
-        println!("got {:?}", structs);
+        let structs = analyze(schema, "Widget", Cfg::default()).unwrap().0;
         let root = &structs[0];
-        assert_eq!(root.name, "Endpoint");
-        assert_eq!(root.level, 0);
-        assert_eq!(root.is_enum, false);
-        assert_eq!(&root.members[0].name, "relabelings");
-        assert_eq!(&root.members[0].type_, "Option<Vec<EndpointRelabelings>>");
-
-        let rel = &structs[1];
-        assert_eq!(rel.name, "EndpointRelabelings");
-        assert_eq!(rel.is_enum, false);
-        assert_eq!(&rel.members[0].name, "action");
-        assert_eq!(&rel.members[0].type_, "Option<EndpointRelabelingsAction>");
-        // TODO: verify rel.members[0].field_annot uses correct default
-
-        // action enum member
-        let act = &structs[2];
-        assert_eq!(act.name, "EndpointRelabelingsAction");
-        assert_eq!(act.is_enum, true);
-
-        // should have enum members:
-        assert_eq!(&act.members[0].name, "replace");
-        assert_eq!(&act.members[0].type_, "");
-        assert_eq!(&act.members[1].name, "keep");
-        assert_eq!(&act.members[1].type_, "");
-        assert_eq!(&act.members[2].name, "drop");
-        assert_eq!(&act.members[2].type_, "");
-        assert_eq!(&act.members[3].name, "hashmod");
-        assert_eq!(&act.members[3].type_, "");
+        let hosts = &root.members[0].constraints;
+        assert_eq!(hosts.min_items, Some(1));
+        assert!(hosts.unique_items);
+        assert_eq!(hosts.pattern, None);
+        let items = hosts.elements.as_ref().unwrap();
+        assert_eq!(items.pattern.as_deref(), Some("^[a-z.]+$"));
+        assert!(!hosts.is_empty());
+        let name = &root.members[1].constraints;
+        assert_eq!(name.pattern.as_deref(), Some("^[a-z]+$"));
+        assert_eq!(name.max_length, Some(63));
+        assert_eq!(name.min_length, None);
+        let replicas = &root.members[2].constraints;
+        assert_eq!(replicas.minimum, Some(0.0));
+        assert_eq!(replicas.maximum, Some(10.0));
+        assert!(replicas.exclusive_maximum);
+        assert!(!replicas.exclusive_minimum);
+        assert_eq!(replicas.elements, None);
+        let selector = &root.members[3].constraints;
+        assert_eq!(selector.max_properties, Some(5));
+        assert_eq!(selector.required, vec!["app".to_string()]);
     }
 
     #[test]
-    #[ignore] // oneof support not done
+    fn unsupported_patterns() {
+        init();
+        let schema_str = r#"
+            properties:
+              name:
+                maxLength: 63
+                pattern: ^(?!kube-).*$
+                type: string
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Widget", Cfg::default()).unwrap().0;
+        // lookarounds are not supported by the regex crate, so the pattern is not checked
+        let name = &structs[0].members[0].constraints;
+        assert_eq!(name.pattern, None);
+        assert_eq!(name.max_length, Some(63));
+    }
+
+    #[test]
+    fn string_formats() {
+        init();
+        let schema_str = r#"
+            properties:
+              lastUpdated:
+                format: date-time
+                type: string
+              releaseDate:
+                format: date
+                type: string
+              port:
+                format: int-or-string
+                type: string
+              history:
+                items:
+                  format: date-time
+                  type: string
+                type: array
+              expiries:
+                additionalProperties:
+                  format: date
+                  type: string
+                type: object
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Release", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].name, "expiries");
+        assert_eq!(root.members[0].type_, "Option<BTreeMap<String, NaiveDate>>");
+        assert_eq!(root.members[1].name, "history");
+        assert_eq!(root.members[1].type_, "Option<Vec<DateTime<Utc>>>");
+        assert_eq!(root.members[2].name, "lastUpdated");
+        assert_eq!(root.members[2].type_, "Option<DateTime<Utc>>");
+        assert_eq!(root.members[3].name, "port");
+        assert_eq!(root.members[3].type_, "Option<IntOrString>");
+        assert_eq!(root.members[4].name, "releaseDate");
+        assert_eq!(root.members[4].type_, "Option<NaiveDate>");
+        assert!(root.uses_datetime(TimeType::Chrono));
+        assert!(root.uses_date(TimeType::Chrono));
+        assert!(root.uses_int_or_string());
+    }
+
+    #[test]
+    fn configurable_time_types() {
+        init();
+        let schema_str = r#"
+            properties:
+              lastUpdated:
+                format: date-time
+                type: string
+              releaseDate:
+                format: date
+                type: string
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        for (time, date_time, date) in [
+            (TimeType::Time, "Option<Time>", "Option<NaiveDate>"),
+            (TimeType::Jiff, "Option<Timestamp>", "Option<Date>"),
+            (TimeType::String, "Option<String>", "Option<String>"),
+        ] {
+            let cfg = Cfg {
+                time,
+                ..Cfg::default()
+            };
+            let structs = analyze(schema.clone(), "Release", cfg).unwrap().0;
+            let root = &structs[0];
+            assert_eq!(root.members[0].type_, date_time);
+            assert_eq!(root.members[1].type_, date);
+            assert!(!root.uses_datetime(TimeType::Chrono));
+        }
+    }
+
+    #[test]
+    fn quantities() {
+        init();
+        let schema_str = r#"
+            properties:
+              limits:
+                additionalProperties:
+                  anyOf:
+                  - type: integer
+                  - type: string
+                  pattern: ^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$
+                  x-kubernetes-int-or-string: true
+                type: object
+              memory:
+                anyOf:
+                - type: integer
+                - type: string
+                pattern: ^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$
+                x-kubernetes-int-or-string: true
+              storage:
+                pattern: ^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$
+                type: string
+              port:
+                anyOf:
+                - type: integer
+                - type: string
+                x-kubernetes-int-or-string: true
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema.clone(), "Volume", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<BTreeMap<String, IntOrString>>");
+        assert_eq!(root.members[1].type_, "Option<IntOrString>");
+        assert!(root.members[1]
+            .extensions
+            .contains_key("x-kubernetes-int-or-string"));
+        assert_eq!(root.members[3].type_, "Option<String>");
+        assert!(!root.uses_quantity());
+
+        let cfg = Cfg {
+            quantity: true,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema, "Volume", cfg).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<BTreeMap<String, Quantity>>");
+        assert_eq!(root.members[1].type_, "Option<Quantity>");
+        // the schema of a Quantity is a string, which must not be marked as int-or-string
+        assert!(root.members[1].extensions.is_empty());
+        assert_eq!(root.members[2].type_, "Option<IntOrString>");
+        assert_eq!(root.members[3].type_, "Option<Quantity>");
+        assert!(root.uses_quantity());
+    }
+
+    #[test]
+    fn durations() {
+        init();
+        let schema_str = r#"
+            properties:
+              interval:
+                pattern: ^(0|(([0-9]+)y)?(([0-9]+)w)?(([0-9]+)d)?(([0-9]+)h)?(([0-9]+)m)?(([0-9]+)s)?(([0-9]+)ms)?)$
+                type: string
+              timeouts:
+                additionalProperties:
+                  pattern: ^([0-9]{1,5}(h|m|s|ms)){1,4}$
+                  type: string
+                type: object
+              backoff:
+                items:
+                  format: duration
+                  type: string
+                type: array
+              name:
+                pattern: ^[a-z]+$
+                type: string
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Cfg {
+            durations: true,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema.clone(), "Scrape", cfg).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<Vec<GoDuration>>");
+        assert_eq!(root.members[1].type_, "Option<GoDuration>");
+        assert_eq!(root.members[2].type_, "Option<String>");
+        assert_eq!(root.members[3].type_, "Option<BTreeMap<String, GoDuration>>");
+        assert!(root.uses_go_duration());
+
+        // opt-in
+        let structs = analyze(schema, "Scrape", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[1].type_, "Option<String>");
+        assert!(!root.uses_go_duration());
+    }
+
+    #[test]
+    fn typed_formats() {
+        init();
+        let schema_str = r#"
+            properties:
+              id:
+                format: uuid
+                type: string
+              endpoint:
+                format: uri
+                type: string
+              addresses:
+                items:
+                  format: ipv4
+                  type: string
+                type: array
+              v6:
+                format: ipv6
+                type: string
+              contacts:
+                additionalProperties:
+                  format: email
+                  type: string
+                type: object
+              host:
+                format: hostname
+                type: string
+              other:
+                format: something
+                type: string
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Cfg {
+            formats: true,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema.clone(), "Endpoint", cfg).unwrap().0;
+        let root = &structs[0];
+        let types: Vec<_> = root.members.iter().map(|m| m.type_.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "Option<Vec<Ipv4Addr>>",
+                "Option<BTreeMap<String, Email>>",
+                "Option<Url>",
+                "Option<Hostname>",
+                "Option<Uuid>",
+                "Option<String>",
+                "Option<Ipv6Addr>",
+            ]
+        );
+        assert!(root.uses_type("Email"));
+
+        // opt-in
+        let structs = analyze(schema, "Endpoint", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<Vec<String>>");
+        assert_eq!(root.members[1].type_, "Option<BTreeMap<String, String>>");
+        assert_eq!(root.members[4].type_, "Option<String>");
+        assert!(!root.uses_type("Uuid"));
+    }
+
+    #[test]
+    fn byte_strings() {
+        init();
+        let schema_str = r#"
+            properties:
+              caBundle:
+                format: byte
+                type: string
+              certificates:
+                items:
+                  format: byte
+                  type: string
+                type: array
+              data:
+                additionalProperties:
+                  format: byte
+                  type: string
+                type: object
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Cfg {
+            bytes: true,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema.clone(), "Webhook", cfg).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<ByteString>");
+        assert_eq!(root.members[1].type_, "Option<Vec<ByteString>>");
+        assert_eq!(root.members[2].type_, "Option<BTreeMap<String, ByteString>>");
+        assert!(root.uses_type("ByteString"));
+
+        // opt-in
+        let structs = analyze(schema, "Webhook", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<String>");
+        assert!(!root.uses_type("ByteString"));
+    }
+
+    #[test]
+    fn secrets() {
+        init();
+        let schema_str = r#"
+            properties:
+              adminPassword:
+                type: string
+              apiToken:
+                type: string
+              keys:
+                additionalProperties:
+                  format: password
+                  type: string
+                type: object
+              tokenTtl:
+                type: integer
+              user:
+                type: string
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Cfg {
+            secrets: true,
+            secret_names: vec![
+                glob::Pattern::new("*token").unwrap(),
+                glob::Pattern::new("*Password").unwrap(),
+            ],
+            ..Cfg::default()
+        };
+        let structs = analyze(schema.clone(), "Database", cfg).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<RedactedString>");
+        assert_eq!(root.members[1].type_, "Option<RedactedString>");
+        assert_eq!(root.members[2].type_, "Option<BTreeMap<String, RedactedString>>");
+        assert_eq!(root.members[3].type_, "Option<i64>");
+        assert_eq!(root.members[4].type_, "Option<String>");
+
+        let structs = analyze(schema, "Database", Cfg::default()).unwrap().0;
+        assert!(!structs[0].uses_type("RedactedString"));
+    }
+
+    #[test]
+    fn narrow_integers() {
+        init();
+        let schema_str = r#"
+            properties:
+              offset:
+                minimum: -1
+                type: integer
+              port:
+                format: int32
+                maximum: 65535
+                minimum: 1
+                type: integer
+              ports:
+                items:
+                  format: int32
+                  maximum: 65535
+                  minimum: 0
+                  type: integer
+                type: array
+              replicas:
+                format: int32
+                minimum: 0
+                type: integer
+              retries:
+                exclusiveMinimum: true
+                minimum: -1
+                type: integer
+              weights:
+                additionalProperties:
+                  exclusiveMaximum: true
+                  maximum: 4294967296
+                  minimum: 0
+                  type: integer
+                type: object
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Cfg {
+            narrow_integers: true,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema.clone(), "Server", cfg).unwrap().0;
+        let types: Vec<_> = structs[0].members.iter().map(|m| m.type_.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "Option<i64>",
+                "Option<u16>",
+                "Option<Vec<u16>>",
+                "Option<u32>",
+                "Option<u64>",
+                "Option<BTreeMap<String, u32>>",
+            ]
+        );
+
+        // opt-in, but array items use their own format
+        let structs = analyze(schema, "Server", Cfg::default()).unwrap().0;
+        let types: Vec<_> = structs[0].members.iter().map(|m| m.type_.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "Option<i64>",
+                "Option<i32>",
+                "Option<Vec<i32>>",
+                "Option<i32>",
+                "Option<i64>",
+                "Option<BTreeMap<String, i64>>",
+            ]
+        );
+    }
+
+    #[test]
+    fn enums_in_arrays_and_maps() {
+        init();
+        let schema_str = r#"
+        properties:
+          modes:
+            additionalProperties:
+              enum:
+              - Read
+              - Write
+              type: string
+            type: object
+          protocols:
+            items:
+              enum:
+              - TCP
+              - UDP
+              type: string
+            type: array
+          rules:
+            items:
+              properties:
+                verbs:
+                  items:
+                    enum:
+                    - get
+                    - list
+                    type: string
+                  type: array
+              type: object
+            type: array
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Policy", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<BTreeMap<String, PolicyModes>>");
+        assert_eq!(root.members[1].type_, "Option<Vec<PolicyProtocols>>");
+        assert_eq!(root.members[2].type_, "Option<Vec<PolicyRules>>");
+
+        let names: Vec<_> = structs.iter().map(|c| (c.name.as_str(), c.is_enum)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Policy", false),
+                ("PolicyModes", true),
+                ("PolicyProtocols", true),
+                ("PolicyRules", false),
+                ("PolicyRulesVerbs", true),
+            ]
+        );
+        assert_eq!(structs[4].members[1].name, "list");
+        assert_eq!(structs[3].members[0].type_, "Option<Vec<PolicyRulesVerbs>>");
+    }
+
+    #[test]
+    fn enums_in_nested_arrays_and_maps() {
+        init();
+        let schema_str = r#"
+        properties:
+          groups:
+            additionalProperties:
+              items:
+                enum:
+                - x
+                - y
+                type: string
+              type: array
+            type: object
+          matrix:
+            items:
+              items:
+                enum:
+                - a
+                - b
+                type: string
+              type: array
+            type: array
+          nested:
+            additionalProperties:
+              additionalProperties:
+                enum:
+                - p
+                - q
+                type: string
+              type: object
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Gadget", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        assert_eq!(
+            root.members[0].type_,
+            "Option<BTreeMap<String, Vec<GadgetGroups>>>"
+        );
+        assert_eq!(root.members[1].type_, "Option<Vec<Vec<GadgetMatrix>>>");
+        assert_eq!(
+            root.members[2].type_,
+            "Option<BTreeMap<String, BTreeMap<String, GadgetNested>>>"
+        );
+
+        let names: Vec<_> = structs.iter().map(|c| (c.name.as_str(), c.is_enum)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Gadget", false),
+                ("GadgetGroups", true),
+                ("GadgetMatrix", true),
+                ("GadgetNested", true),
+            ]
+        );
+        assert_eq!(structs[3].members[1].name, "q");
+    }
+
+    #[test]
+    fn boolean_in_additionals() {
+        // as found in argo-app
+        init();
+        let schema_str = r#"
+            properties:
+              options:
+                additionalProperties:
+                  type: boolean
+                type: object
+              patch:
+                type: string
+            type: object
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Options", Cfg::default()).unwrap().0;
+        println!("got {:?}", structs);
+        let root = &structs[0];
+        assert_eq!(root.name, "Options");
+        assert_eq!(root.level, 0);
+        assert_eq!(&root.members[0].name, "options");
+        assert_eq!(&root.members[0].type_, "Option<BTreeMap<String, bool>>");
+    }
+
+    #[test]
+    fn enum_string() {
+        init();
+        let schema_str = r#"
+      properties:
+        operator:
+          enum:
+          - In
+          - NotIn
+          - Exists
+          - DoesNotExist
+          type: string
+      required:
+      - operator
+      type: object
+"#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "MatchExpressions", Cfg::default()).unwrap().0;
+        println!("got {:?}", structs);
+        let root = &structs[0];
+        assert_eq!(root.name, "MatchExpressions");
+        assert_eq!(root.level, 0);
+        assert_eq!(&root.members[0].name, "operator");
+        assert_eq!(&root.members[0].type_, "MatchExpressionsOperator");
+
+        // operator member
+        let op = &structs[1];
+        assert!(op.is_enum);
+        assert_eq!(op.name, "MatchExpressionsOperator");
+
+        // should have enum members:
+        assert_eq!(&op.members[0].name, "In");
+        assert_eq!(&op.members[0].type_, "");
+        assert_eq!(&op.members[1].name, "NotIn");
+        assert_eq!(&op.members[1].type_, "");
+        assert_eq!(&op.members[2].name, "Exists");
+        assert_eq!(&op.members[2].type_, "");
+        assert_eq!(&op.members[3].name, "DoesNotExist");
+        assert_eq!(&op.members[3].type_, "");
+    }
+
+    #[test]
+    fn enum_string_within_container() {
+        init();
+        let schema_str = r#"
+      description: Endpoint
+      properties:
+        relabelings:
+          items:
+            properties:
+              action:
+                default: replace
+                enum:
+                - replace
+                - keep
+                - drop
+                - hashmod
+                - labelmap
+                - labeldrop
+                - labelkeep
+                type: string
+              modulus:
+                format: int64
+                type: integer
+            type: object
+          type: array
+      type: object
+        "#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Endpoint", Cfg::default()).unwrap().0;
+        println!("got {:?}", structs);
+        let root = &structs[0];
+        assert_eq!(root.name, "Endpoint");
+        assert_eq!(root.level, 0);
+        assert_eq!(root.is_enum, false);
+        assert_eq!(&root.members[0].name, "relabelings");
+        assert_eq!(&root.members[0].type_, "Option<Vec<EndpointRelabelings>>");
+
+        let rel = &structs[1];
+        assert_eq!(rel.name, "EndpointRelabelings");
+        assert_eq!(rel.is_enum, false);
+        assert_eq!(&rel.members[0].name, "action");
+        assert_eq!(&rel.members[0].type_, "Option<EndpointRelabelingsAction>");
+        // TODO: verify rel.members[0].field_annot uses correct default
+
+        // action enum member
+        let act = &structs[2];
+        assert_eq!(act.name, "EndpointRelabelingsAction");
+        assert_eq!(act.is_enum, true);
+
+        // should have enum members:
+        assert_eq!(&act.members[0].name, "replace");
+        assert_eq!(&act.members[0].type_, "");
+        assert_eq!(&act.members[1].name, "keep");
+        assert_eq!(&act.members[1].type_, "");
+        assert_eq!(&act.members[2].name, "drop");
+        assert_eq!(&act.members[2].type_, "");
+        assert_eq!(&act.members[3].name, "hashmod");
+        assert_eq!(&act.members[3].type_, "");
+
+        // default variant from the schema
+        assert_eq!(act.members[0].extra_annot, vec!["#[default]".to_string()]);
+        assert!(act.members[1..].iter().all(|m| m.extra_annot.is_empty()));
+        assert!(act.has_default_variant());
+    }
+
+    #[test]
+    #[ignore] // oneof support not done
     fn enum_oneof() {
         init();
         let schema_str = r#"
@@ -996,7 +2134,7 @@ type: object
         // should have an params member:
         let member = &eps.members[0];
         assert_eq!(member.name, "params");
-        assert_eq!(member.type_, "Option<BTreeMap<String, String>>");
+        assert_eq!(member.type_, "Option<BTreeMap<String, Vec<String>>>");
     }
 
     #[test]
@@ -1064,7 +2202,6 @@ type: object
     }
 
     #[test]
-    #[ignore] // currently do not handle top level enums, and this has an integration test
     fn top_level_enum_with_integers() {
         init();
         let schema_str = r#"
@@ -1082,9 +2219,68 @@ type: object
         assert_eq!(root.name, "StatusCode");
         assert_eq!(root.level, 0);
         assert_eq!(root.is_enum, true);
-        assert_eq!(&root.members[0].name, "301");
-        assert_eq!(&root.members[0].name, "302");
+        assert!(root.is_non_string_enum());
+        assert_eq!(&root.members[0].name, "Value301");
+        assert_eq!(&root.members[1].name, "Value302");
         assert_eq!(&root.members[0].type_, "");
+        assert_eq!(root.members[1].value, Some(serde_json::json!(302)));
+    }
+
+    #[test]
+    fn non_string_enums() {
+        init();
+        let schema_str = r#"
+        properties:
+          enabled:
+            enum:
+            - true
+            type: boolean
+          port:
+            anyOf:
+            - type: integer
+            - type: string
+            enum:
+            - 80
+            - http
+            x-kubernetes-int-or-string: true
+          priority:
+            description: Priority of the rule
+            enum:
+            - -1
+            - 0
+            - 1
+            type: integer
+          ratio:
+            enum:
+            - 0.5
+            - 1
+            type: number
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Rule", Cfg::default()).unwrap().0;
+        let root = &structs[0];
+        let types: Vec<_> = root.members.iter().map(|m| m.type_.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "Option<RuleEnabled>",
+                "Option<RulePort>",
+                "Option<RulePriority>",
+                "Option<RuleRatio>",
+            ]
+        );
+        let names = |c: &crate::Container| c.members.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+        assert_eq!(structs[1].name, "RuleEnabled");
+        assert_eq!(names(&structs[1]), vec!["True"]);
+        assert_eq!(structs[2].name, "RulePort");
+        assert_eq!(names(&structs[2]), vec!["Value80", "http"]);
+        assert_eq!(structs[3].name, "RulePriority");
+        assert_eq!(structs[3].level, 1);
+        assert_eq!(structs[3].docs.as_deref(), Some("Priority of the rule"));
+        assert_eq!(names(&structs[3]), vec!["ValueMinus1", "Value0", "Value1"]);
+        assert_eq!(names(&structs[4]), vec!["Value0Point5", "Value1"]);
+        assert!(structs[1..].iter().all(|c| c.is_enum && c.is_non_string_enum()));
     }
 
     #[test]
@@ -1251,4 +2447,275 @@ type: object
         assert_eq!(structs[0].members.len(), 1);
         assert_eq!(structs[0].members[0].type_, "Option<Vec<Condition>>");
     }
+
+    #[test]
+    fn overrides_by_path() {
+        init();
+        let schema_str = r#"
+        properties:
+          endpoints:
+            items:
+              properties:
+                port:
+                  type: string
+                tls:
+                  properties:
+                    ca:
+                      type: string
+                  type: object
+              required:
+              - port
+              type: object
+            type: array
+          podTemplate:
+            properties:
+              spec:
+                properties:
+                  containers:
+                    type: string
+                type: object
+            type: object
+          selector:
+            properties:
+              app:
+                type: string
+            type: object
+          timeout:
+            type: string
+        required:
+        - timeout
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let overrides = [
+            (
+                "endpoints",
+                Override {
+                    rename: Some("Endpoint".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "endpoints.port",
+                Override {
+                    type_: Some("u16".into()),
+                    required: Some(false),
+                    member_attrs: vec!["#[doc(alias = \"p\")]".into()],
+                    ..Default::default()
+                },
+            ),
+            (
+                "endpoints.tls",
+                Override {
+                    elide: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "podTemplate",
+                Override {
+                    use_: Some("k8s_openapi::api::core::v1::PodTemplateSpec".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "selector",
+                Override {
+                    type_: Some("BTreeMap<String, String>".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "timeout",
+                Override {
+                    required: Some(false),
+                    ..Default::default()
+                },
+            ),
+        ];
+        let cfg = Cfg {
+            overrides: overrides.into_iter().map(|(p, o)| (p.to_string(), o)).collect(),
+            ..Cfg::default()
+        };
+        let structs = analyze(schema, "Monitor", cfg).unwrap().0;
+        let paths: Vec<_> = structs
+            .iter()
+            .map(|c| (c.name.as_str(), c.path.as_str()))
+            .collect();
+        assert_eq!(paths, vec![("Monitor", ""), ("Endpoint", "endpoints")]);
+
+        let root = &structs[0];
+        assert_eq!(root.members[0].type_, "Option<Vec<Endpoint>>");
+        assert_eq!(
+            root.members[1].type_,
+            "Option<k8s_openapi::api::core::v1::PodTemplateSpec>"
+        );
+        assert_eq!(root.members[2].type_, "Option<BTreeMap<String, String>>");
+        assert_eq!(root.members[3].type_, "Option<String>");
+
+        let endpoint = &structs[1];
+        assert_eq!(endpoint.members[0].type_, "Option<u16>");
+        assert_eq!(endpoint.members[0].extra_annot, vec!["#[doc(alias = \"p\")]"]);
+        // elided containers are still referenced
+        assert_eq!(endpoint.members[1].type_, "Option<EndpointTls>");
+    }
+
+    #[test]
+    fn analyze_subtree_at_path() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              endpoints:
+                items:
+                  properties:
+                    port:
+                      type: string
+                    tls:
+                      properties:
+                        ca:
+                          type: string
+                      type: object
+                  type: object
+                type: array
+              replicas:
+                type: integer
+            type: object
+          status:
+            properties:
+              ready:
+                type: boolean
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze_path(schema.clone(), "spec.endpoints", "Monitor", Cfg::default())
+            .unwrap()
+            .0;
+        let names: Vec<_> = structs
+            .iter()
+            .map(|c| (c.name.as_str(), c.path.as_str(), c.level))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("MonitorSpecEndpoints", "spec.endpoints", 2),
+                ("MonitorSpecEndpointsTls", "spec.endpoints.tls", 3),
+            ]
+        );
+        assert_eq!(structs[0].members[1].type_, "Option<MonitorSpecEndpointsTls>");
+
+        let structs = analyze_path(schema.clone(), "status", "Monitor", Cfg::default())
+            .unwrap()
+            .0;
+        assert_eq!(structs.len(), 1);
+        assert!(structs[0].is_status_container());
+
+        assert!(analyze_path(schema.clone(), "spec.missing", "Monitor", Cfg::default()).is_err());
+        assert!(analyze_path(schema, "spec.replicas", "Monitor", Cfg::default()).is_err());
+    }
+
+    #[test]
+    fn naming_strategies() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              storage:
+                properties:
+                  template:
+                    properties:
+                      dataSourceRef:
+                        properties:
+                          name:
+                            type: string
+                        type: object
+                      selector:
+                        properties:
+                          app:
+                            type: string
+                        type: object
+                    type: object
+                type: object
+              selector:
+                title: pod selector
+                properties:
+                  app:
+                    type: string
+                type: object
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let names = |cfg: Cfg| -> Vec<String> {
+            let output = analyze(schema.clone(), "Prometheus", cfg).unwrap();
+            output.0.iter().map(|c| c.name.clone()).collect()
+        };
+        assert_eq!(
+            names(Cfg::default()),
+            vec![
+                "Prometheus",
+                "PrometheusSpec",
+                "PrometheusSpecSelector",
+                "PrometheusSpecStorage",
+                "PrometheusSpecStorageTemplate",
+                "PrometheusSpecStorageTemplateDataSourceRef",
+                "PrometheusSpecStorageTemplateSelector",
+            ]
+        );
+
+        // shared suffixes are made longer, and names of other containers are not reused
+        let suffix = Cfg {
+            naming: Naming::Suffix,
+            ..Cfg::default()
+        };
+        assert_eq!(
+            names(suffix),
+            vec![
+                "Prometheus",
+                "PrometheusSpec",
+                "PrometheusSpecSelector",
+                "PrometheusStorage",
+                "PrometheusTemplate",
+                "PrometheusDataSourceRef",
+                "PrometheusTemplateSelector",
+            ]
+        );
+
+        let title = Cfg {
+            naming: Naming::Title,
+            ..Cfg::default()
+        };
+        assert_eq!(names(title)[2], "PrometheusPodSelector");
+
+        // long names are shortened to a suffix that fits, or truncated and numbered,
+        // while names given by overrides are kept
+        let rename = Override {
+            rename: Some("VolumeStorage".into()),
+            ..Default::default()
+        };
+        let short = Cfg {
+            max_name_length: Some(20),
+            overrides: [("spec.storage".to_string(), rename)].into_iter().collect(),
+            ..Cfg::default()
+        };
+        let output = analyze(schema, "Prometheus", short).unwrap();
+        let names: Vec<_> = output.0.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Prometheus",
+                "PrometheusSpec",
+                "PrometheusSpecSelector",
+                "VolumeStorage",
+                "PrometheusTemplate",
+                "PrometheusDataSourc2",
+                "PrometheusSelector2",
+            ]
+        );
+        // the type of the member is renamed with its container
+        assert_eq!(output.0[4].members[0].type_, "Option<PrometheusDataSourc2>");
+    }
 }
@@ -1,6 +1,6 @@
 #[macro_use] extern crate log;
 
 mod analyzer;
-pub use analyzer::{analyze, Config};
+pub use analyzer::{analyze, analyze_path, Config, Override};
 mod output;
-pub use output::{Container, MapType, Member, Output};
+pub use output::{Constraints, Container, Lock, MapType, Member, Naming, Output, TimeType};
@@ -1,13 +1,18 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 #[macro_use] extern crate log;
 use anyhow::{anyhow, Context, Result};
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{
+    error::ErrorKind, parser::ValueSource, ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser,
+    Subcommand,
+};
+use heck::{ToShoutySnakeCase, ToSnakeCase};
 use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
-    CustomResourceDefinition, CustomResourceDefinitionVersion,
+    CustomResourceDefinition, CustomResourceDefinitionVersion, ValidationRule,
 };
-use kopium::{analyze, Config, Container, MapType};
+use kopium::{analyze, analyze_path, Config, Container, Lock, MapType, Member, Naming, Override, TimeType};
 use kube::{api, core::Version, Api, Client, ResourceExt};
 use quote::format_ident;
+use serde::Deserialize;
 
 #[derive(Parser)]
 #[command(
@@ -56,6 +61,7 @@ struct Kopium {
     ///
     /// --schema=derived implies `--derive JsonSchema`. The resulting schema will compile without external user action.
     /// The crd via `CustomResourceExt::crd()` can be applied into Kubernetes directly.
+    /// Kubernetes extensions such as `x-kubernetes-preserve-unknown-fields` are retained via `schemars(schema_with)` helpers.
     #[arg(
         long,
         default_value = "disabled",
@@ -64,11 +70,25 @@ struct Kopium {
     schema: String,
 
     /// Derive these extra traits on generated structs
-    #[arg(long,
-        short = 'D',
-        value_parser = ["Copy", "Default", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash", "JsonSchema"],
-    )]
-    derive: Vec<String>,
+    ///
+    /// Takes a trait path, such as "PartialEq" or "utoipa::ToSchema", optionally prefixed by a target:
+    /// a container name or glob ("Foo*=Hash"), "@struct" or "@enum" ("@enum=Hash").
+    ///
+    /// Traits that are not implemented by all member types of a container, such as Eq
+    /// with an f64 member, are skipped for that container with a warning.
+    #[arg(long, short = 'D', value_parser = parse_derive)]
+    derive: Vec<Targeted>,
+
+    /// Add these attributes to generated containers or their members
+    ///
+    /// Takes an attribute, such as '#[serde(deny_unknown_fields)]', optionally prefixed by a target:
+    /// a container name or glob, "@struct", "@enum", or a member as "Container.member" with globs for either.
+    #[arg(long, value_parser = parse_attr)]
+    attr: Vec<Targeted>,
+
+    /// Import these paths in the prelude, such as "utoipa::ToSchema"
+    #[arg(long, value_parser = parse_import)]
+    import: Vec<String>,
 
     #[command(subcommand)]
     command: Option<Command>,
@@ -86,9 +106,28 @@ struct Kopium {
     /// Elide the following containers from the output
     ///
     /// This allows manual customization of structs from the output without having to remove it from
-    /// the output first. Takes precise generated struct names.
-    #[arg(long, short = 'e')]
-    elide: Vec<String>,
+    /// the output first. Takes generated struct names, globs such as "*Selector",
+    /// or regular expressions prefixed by "re:". Patterns that match nothing are warned about.
+    #[arg(long, short = 'e', value_parser = parse_elide)]
+    elide: Vec<Elide>,
+
+    /// Also elide the containers that are only referenced by elided containers
+    #[arg(long)]
+    prune_elided: bool,
+
+    /// Only generate the containers at and below a JSON path, such as "status" or "spec.endpoints"
+    ///
+    /// The CustomResource is not generated, as with --hide-kube.
+    #[arg(long)]
+    only: Option<String>,
+
+    /// Use an external type in place of a generated container, such as
+    /// "spec.podTemplate=k8s_openapi::api::core::v1::PodTemplateSpec"
+    ///
+    /// The container is given by the JSON path of its property, or by its generated name.
+    /// Nothing is generated below it, and containers that are no longer referenced are left out.
+    #[arg(long = "use", value_parser = parse_use)]
+    uses: Vec<(String, String)>,
 
     /// Relaxed interpretation
     ///
@@ -104,9 +143,377 @@ struct Kopium {
     #[arg(long)]
     no_condition: bool,
 
+    /// Use the Quantity type from k8s_openapi for properties with the kubernetes quantity pattern
+    ///
+    /// These are otherwise an IntOrString or a String.
+    /// NB: Quantity only deserializes from strings, so integer values need to be quoted,
+    /// and the schema of integer or string quantities only accepts strings.
+    #[arg(long)]
+    quantity: bool,
+
+    /// Use a generated GoDuration type for duration strings
+    ///
+    /// Strings with the duration format, or with a pattern for go duration strings such as "1h30m",
+    /// become a GoDuration newtype wrapping a std::time::Duration. It (de)serializes the go format
+    /// along with the day, week and year units used by prometheus.
+    #[arg(long)]
+    typed_durations: bool,
+
+    /// Use typed members for strings with uuid, uri, ipv4, ipv6, email and hostname formats
+    ///
+    /// These become uuid::Uuid, url::Url, std::net::Ipv4Addr and std::net::Ipv6Addr,
+    /// or generated Email and Hostname newtypes that are validated when deserializing.
+    /// The uuid and url crates need their serde features (and schemars its uuid1 and url features).
+    #[arg(long)]
+    typed_formats: bool,
+
+    /// Use the ByteString type from k8s_openapi for strings with the byte format
+    ///
+    /// ByteString holds the decoded bytes, and (de)serializes the base64 encoded string.
+    #[arg(long)]
+    byte_strings: bool,
+
+    /// Use a generated RedactedString type for strings with the password format
+    ///
+    /// RedactedString keeps the value out of Debug output, so derived Debug impls can be logged safely.
+    #[arg(long)]
+    secrets: bool,
+
+    /// Use a RedactedString for string members with names matching a pattern, such as "*Token"
+    ///
+    /// Patterns are case insensitive globs matched against the property names in the schema.
+    #[arg(long, value_parser = glob::Pattern::new)]
+    secret_name: Vec<glob::Pattern>,
+
+    /// Use unsigned integer types for integers with a non-negative minimum
+    ///
+    /// The type is narrowed to u16 or u32 when the maximum allows it, without going wider than the format.
+    /// A port with format int32 and a maximum of 65535 becomes a u16, and a replica count with a minimum of 0 a u32.
+    #[arg(long)]
+    narrow_integers: bool,
+
+    /// Generate a validated newtype for every distinct pattern on string members
+    ///
+    /// The newtypes check the pattern when created through TryFrom, FromStr or deserialization,
+    /// and dereference to str. The generated code requires the regex crate.
+    #[arg(long)]
+    pattern_types: bool,
+
+    /// Add a catch-all variant to enums, and mark them non_exhaustive
+    ///
+    /// Lets older controllers deserialize objects using enum values added in later versions of a CRD.
+    /// The variant is left out of the JsonSchema, so the generated schema keeps the original values.
+    #[arg(long, value_enum)]
+    enum_fallback: Option<EnumFallback>,
+
+    /// Generate Display, FromStr, AsRef<str> and a list of ALL variants for string enums
+    ///
+    /// These use the values from the schema, so they match the serialized form of the variants.
+    #[arg(long)]
+    enum_impls: bool,
+
     /// Type used to represent maps via additionalProperties
     #[arg(long, value_enum, default_value_t)]
     map_type: MapType,
+
+    /// Type used to represent strings with date and date-time formats
+    ///
+    /// The crate providing the type needs its serde support enabled. Deriving JsonSchema also needs the
+    /// schemars support of chrono or k8s_openapi, while jiff types are described by generated schema types.
+    #[arg(long, value_enum, default_value_t)]
+    time_type: TimeType,
+
+    /// Generate validate_rules methods from x-kubernetes-validations CEL rules
+    ///
+    /// Every generated struct gets a `validate_rules` method that evaluates the CEL rules found in the schema
+    /// and reports the failing rules with their message and field path. Rules are compiled once, on first use.
+    /// Transition rules referencing `oldSelf` are skipped.
+    ///
+    /// The generated code requires the cel-interpreter crate.
+    #[arg(long)]
+    cel_validation: bool,
+
+    /// Generate validation code from schema constraints
+    ///
+    /// Constraints such as pattern, minimum/maximum, lengths, item and property counts,
+    /// uniqueItems and required keys of maps are either checked in a generated `validate_rules` method,
+    /// or passed on to the garde or validator crates via derives and field attributes.
+    #[arg(long, value_enum)]
+    constraints: Option<ConstraintMode>,
+
+    /// Read options and overrides from a kopium.toml or kopium.yaml file
+    ///
+    /// Top level keys are the long names of the options above. Options given on the command line
+    /// replace those from the file, and flags from the file can be turned off with `--flag=false`.
+    /// Overrides are keyed by the JSON path of a property, such as "spec.endpoints",
+    /// and can rename its container or replace it with an external type (`rename`, `use`),
+    /// elide it (`elide`), set the member type or whether it is required (`type`, `required`),
+    /// and add attributes to the container or member (`attrs`, `member_attrs`).
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Keep generated names stable using a lock file, such as kopium.lock
+    ///
+    /// The file records the container and member names of each JSON path, and is created or updated
+    /// on every run. Paths recorded in it keep their names, so only new paths are given new names.
+    #[arg(long)]
+    lock: Option<PathBuf>,
+
+    /// How nested containers are named
+    ///
+    /// Names that would clash with another container fall back to the full name.
+    #[arg(long, value_enum, default_value_t)]
+    naming: Naming,
+
+    /// Shorten the names of nested containers longer than this, using a suffix of their JSON path
+    ///
+    /// Names are truncated and numbered when no suffix that fits is unique.
+    #[arg(long)]
+    max_name_length: Option<usize>,
+
+    /// Overrides by JSON path from the config file
+    #[arg(skip)]
+    overrides: BTreeMap<String, Override>,
+}
+
+/// The contents of a config file
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    overrides: BTreeMap<String, Override>,
+    #[serde(flatten)]
+    options: BTreeMap<String, serde_json::Value>,
+}
+
+impl ConfigFile {
+    fn read(path: &std::path::Path) -> Result<Self> {
+        let data =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let config = if path.extension().is_some_and(|e| e == "toml") {
+            toml::from_str(&data)?
+        } else {
+            serde_yaml::from_str(&data)?
+        };
+        Ok(config)
+    }
+
+    /// The options as command line arguments, leaving out those replaced by the command line
+    fn args(&self, cli: &ArgMatches) -> Result<Vec<String>> {
+        let mut command = Kopium::cli();
+        command.build();
+        // the crd and --filename conflict, so giving either replaces both
+        let replaced: Vec<_> = command
+            .get_arguments()
+            .filter(|a| cli.value_source(a.get_id().as_str()) == Some(ValueSource::CommandLine))
+            .flat_map(|a| command.get_arg_conflicts_with(a).into_iter().chain([a]))
+            .map(|a| a.get_id())
+            .collect();
+        let mut args = vec![];
+        for (key, value) in &self.options {
+            let long = key.replace('_', "-");
+            let Some(arg) = command
+                .get_arguments()
+                .find(|a| a.get_long() == Some(long.as_str()) || (key == "crd" && a.get_id() == "crd"))
+            else {
+                return Err(anyhow!("unknown option {} in config file", key));
+            };
+            if replaced.contains(&arg.get_id()) {
+                continue;
+            }
+            let flag = format!("--{long}");
+            let values = match value {
+                serde_json::Value::Bool(value) => {
+                    args.push(format!("{flag}={value}"));
+                    continue;
+                }
+                serde_json::Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            };
+            for value in values {
+                let value = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Number(n) => n.to_string(),
+                    v => return Err(anyhow!("unsupported value {} for option {}", v, key)),
+                };
+                if key == "crd" {
+                    args.push(value); // positional
+                } else {
+                    args.push(format!("{flag}={value}"));
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Catch-all variant added to enums for values missing from the schema
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum EnumFallback {
+    /// A unit variant deserialized from any unknown value (serialized as "Unknown")
+    Unknown,
+    /// A variant preserving the unknown value, as a String for string enums
+    Other,
+}
+
+/// How schema constraints are turned into validation code
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ConstraintMode {
+    /// Checks in the generated validate methods (requires the regex crate for patterns)
+    Generated,
+    /// garde::Validate derives with garde field attributes
+    Garde,
+    /// validator::Validate derives with validate field attributes
+    Validator,
+}
+
+/// A derive or attribute for the generated containers or members matching a target
+#[derive(Clone, Debug)]
+struct Targeted {
+    target: Target,
+    value: String,
+}
+
+/// Which generated containers or members a derive or attribute applies to
+#[derive(Clone, Debug)]
+enum Target {
+    All,
+    Structs,
+    Enums,
+    Containers(glob::Pattern),
+    Members(glob::Pattern, glob::Pattern),
+}
+
+impl Target {
+    fn parse(target: &str) -> Result<Self, String> {
+        let pattern = |p: &str| glob::Pattern::new(p).map_err(|e| format!("invalid pattern {p:?}: {e}"));
+        Ok(match target {
+            "@struct" => Self::Structs,
+            "@enum" => Self::Enums,
+            t => match t.split_once('.') {
+                Some((container, member)) => Self::Members(pattern(container)?, pattern(member)?),
+                None => Self::Containers(pattern(t)?),
+            },
+        })
+    }
+
+    /// Whether a container is targeted, by its generated name or the name it is printed with
+    fn matches_container(&self, s: &Container, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Structs => !s.is_enum,
+            Self::Enums => s.is_enum,
+            Self::Containers(p) => p.matches(&s.name) || p.matches(name),
+            Self::Members(..) => false,
+        }
+    }
+
+    fn matches_member(&self, s: &Container, name: &str, m: &Member) -> bool {
+        match self {
+            Self::Members(c, p) => {
+                (c.matches(&s.name) || c.matches(name)) && p.matches(m.name.trim_start_matches("r#"))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => Ok(()),
+            Self::Structs => write!(f, "@struct"),
+            Self::Enums => write!(f, "@enum"),
+            Self::Containers(p) => write!(f, "{p}"),
+            Self::Members(c, m) => write!(f, "{c}.{m}"),
+        }
+    }
+}
+
+/// A pattern for container names to elide
+#[derive(Clone, Debug)]
+enum Elide {
+    Glob(glob::Pattern),
+    Regex(String, regex::Regex),
+}
+
+impl Elide {
+    /// Whether a container is elided, by its generated name or the name it is printed with
+    fn matches(&self, s: &Container, name: &str) -> bool {
+        match self {
+            Self::Glob(p) => p.matches(&s.name) || p.matches(name),
+            Self::Regex(_, r) => r.is_match(&s.name) || r.is_match(name),
+        }
+    }
+}
+
+impl std::fmt::Display for Elide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Glob(p) => write!(f, "{p}"),
+            Self::Regex(regex, _) => write!(f, "re:{regex}"),
+        }
+    }
+}
+
+/// Parse an elided container name, glob, or regex as `re:REGEX`
+fn parse_elide(arg: &str) -> Result<Elide, String> {
+    if let Some(regex) = arg.strip_prefix("re:") {
+        // anchored, like the globs and names
+        let anchored = regex::Regex::new(&format!("^(?:{regex})$")).map_err(|e| e.to_string())?;
+        Ok(Elide::Regex(regex.to_string(), anchored))
+    } else {
+        let glob = glob::Pattern::new(arg).map_err(|e| format!("invalid pattern {arg:?}: {e}"))?;
+        Ok(Elide::Glob(glob))
+    }
+}
+
+/// Parse a derive as `[TARGET=]PATH`
+fn parse_derive(arg: &str) -> Result<Targeted, String> {
+    let (target, value) = match arg.split_once('=') {
+        Some((target, value)) => (Target::parse(target)?, value),
+        None => (Target::All, arg),
+    };
+    if matches!(target, Target::Members(..)) {
+        return Err("derives can only target containers".to_string());
+    }
+    syn::parse_str::<syn::Path>(value).map_err(|e| format!("invalid derive {value:?}: {e}"))?;
+    Ok(Targeted {
+        target,
+        value: value.to_string(),
+    })
+}
+
+/// Parse an attribute as `[TARGET=]#[ATTR]`
+fn parse_attr(arg: &str) -> Result<Targeted, String> {
+    use syn::parse::Parser;
+    let (target, value) = match arg.split_once('=') {
+        // the = may belong to the attribute, such as in #[serde(rename = "x")]
+        Some((target, value)) if !target.trim_start().starts_with('#') => (Target::parse(target)?, value),
+        _ => (Target::All, arg),
+    };
+    match syn::Attribute::parse_outer.parse_str(value) {
+        Ok(attrs) if !attrs.is_empty() => Ok(Targeted {
+            target,
+            value: value.trim().to_string(),
+        }),
+        Ok(_) => Err("expected an attribute such as #[serde(deny_unknown_fields)]".to_string()),
+        Err(e) => Err(format!("invalid attribute {value:?}: {e}")),
+    }
+}
+
+/// Parse a substitution as `PATH=TYPE`, where PATH is a JSON path or a generated container name
+fn parse_use(arg: &str) -> Result<(String, String), String> {
+    let (target, type_) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected PATH=TYPE, got {arg:?}"))?;
+    syn::parse_str::<syn::Type>(type_).map_err(|e| format!("invalid type {type_:?}: {e}"))?;
+    Ok((target.to_string(), type_.to_string()))
+}
+
+/// Parse the path of a use declaration, such as `utoipa::ToSchema`
+fn parse_import(arg: &str) -> Result<String, String> {
+    syn::parse_str::<syn::Path>(arg).map_err(|e| format!("invalid import {arg:?}: {e}"))?;
+    Ok(arg.trim().to_string())
 }
 
 #[derive(Clone, Copy, Debug, Subcommand)]
@@ -131,17 +538,59 @@ async fn main() -> Result<()> {
         libc::signal(libc::SIGPIPE, libc::SIG_DFL);
     }
 
-    let mut args = Kopium::parse();
+    let matches = Kopium::cli().try_get_matches().unwrap_or_else(|e| {
+        if e.kind() == ErrorKind::DisplayHelp {
+            // the plain command prints the same help, without the `[=<BOOL>]` of every flag
+            Kopium::command().get_matches();
+        }
+        e.exit()
+    });
+    let mut args = Kopium::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Some(path) = args.config.as_deref() {
+        let config = ConfigFile::read(path)?;
+        let argv = std::env::args()
+            .take(1)
+            .chain(config.args(&matches)?)
+            .chain(std::env::args().skip(1));
+        let matches = Kopium::cli()
+            .try_get_matches_from(argv)
+            .unwrap_or_else(|e| e.exit());
+        args = Kopium::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        args.overrides = config.overrides;
+    }
+    if args.only.is_some() {
+        args.hide_kube = true;
+    }
     if args.auto {
         args.docs = true;
         args.schema = "derived".into();
     }
-    if args.schema == "derived" && !args.derive.contains(&"JsonSchema".to_string()) {
-        args.derive.push("JsonSchema".to_string());
+    if args.schema == "derived" && !args.derives_json_schema() {
+        args.derive.push(Targeted {
+            target: Target::All,
+            value: "JsonSchema".to_string(),
+        });
     }
     args.dispatch().await
 }
 
+/// Read a lock file, or an empty lock if it does not exist yet
+fn read_lock(path: &std::path::Path) -> Result<Lock> {
+    if !path.exists() {
+        return Ok(Lock::default());
+    }
+    let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn write_lock(path: &std::path::Path, lock: &Lock) -> Result<()> {
+    let data = format!(
+        "# Generated by kopium to keep names stable, edit to rename types\n{}",
+        toml::to_string(lock)?
+    );
+    std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+}
+
 fn get_stdin_data() -> Result<String> {
     use std::io::{stdin, Read};
     let mut buf = Vec::new();
@@ -151,6 +600,24 @@ fn get_stdin_data() -> Result<String> {
 }
 
 impl Kopium {
+    /// The command line interface, where flags also take a value to turn off flags from a config file
+    ///
+    /// Help is printed by the plain command instead, which lists the flags without that value.
+    fn cli() -> clap::Command {
+        Self::command().mut_args(|a| match a.get_action() {
+            ArgAction::SetTrue => a
+                .action(ArgAction::Set)
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_value("false")
+                .default_missing_value("true")
+                .value_parser(clap::value_parser!(bool))
+                .value_name("BOOL")
+                .hide_possible_values(true),
+            _ => a,
+        })
+    }
+
     async fn dispatch(&self) -> Result<()> {
         if let Some(name) = self.crd.as_deref() {
             let api = Client::try_default()
@@ -203,29 +670,126 @@ impl Kopium {
             log::debug!("schema: {}", serde_json::to_string_pretty(&schema)?);
             let cfg = Config {
                 no_condition: self.no_condition,
+                quantity: self.quantity,
+                durations: self.typed_durations,
+                formats: self.typed_formats,
+                bytes: self.byte_strings,
+                secrets: self.secrets,
+                secret_names: self.secret_name.clone(),
+                narrow_integers: self.narrow_integers,
                 map: self.map_type,
                 relaxed: self.relaxed,
+                time: self.time_type,
+                naming: self.naming,
+                max_name_length: self.max_name_length,
+                overrides: self.path_overrides(),
+            };
+            let mut output = match &self.only {
+                Some(path) => analyze_path(schema, path, kind, cfg)?,
+                None => analyze(schema, kind, cfg)?,
+            }
+            .rename();
+            if let Some(path) = &self.lock {
+                let mut lock = read_lock(path)?;
+                output.lock_names(&lock);
+                let current = Lock::from_output(&output);
+                lock.containers.extend(current.containers);
+                lock.members.extend(current.members);
+                write_lock(path, &lock)?;
+            }
+            if !self.uses.is_empty() {
+                for (name, type_) in self.uses.iter().filter(|(t, _)| is_container_name(t)) {
+                    let found = [name.clone(), name.replacen(kind, &format!("{kind}Spec"), 1)]
+                        .iter()
+                        .any(|name| output.use_type(name, type_));
+                    if !found {
+                        warn!("no generated container named {} to use {} for", name, type_);
+                    }
+                }
+                for name in output.prune(&[]) {
+                    debug!("pruning unreferenced {} from the output", name);
+                }
+            }
+            let targeted = self.derive.iter().map(|t| ("--derive", t));
+            for (arg, t) in targeted.chain(self.attr.iter().map(|t| ("--attr", t))) {
+                let matched = output.0.iter().filter(|c| c.level > 0).any(|c| {
+                    let name = display_name(c, kind);
+                    t.target.matches_container(c, &name)
+                        || c.members.iter().any(|m| t.target.matches_member(c, &name, m))
+                });
+                if !matched && !matches!(t.target, Target::All) {
+                    let what = match t.target {
+                        Target::Members(..) => "member",
+                        _ => "container",
+                    };
+                    warn!(
+                        "{} {}={} does not match any generated {}",
+                        arg, t.target, t.value, what
+                    );
+                }
+            }
+            for e in &self.elide {
+                if !output
+                    .0
+                    .iter()
+                    .any(|c| c.level > 0 && e.matches(c, &display_name(c, kind)))
+                {
+                    warn!("--elide {} does not match any generated container", e);
+                }
+            }
+            let elided: Vec<String> = output
+                .0
+                .iter()
+                .filter(|c| self.elides(c, kind))
+                .map(|c| c.name.clone())
+                .collect();
+            if self.prune_elided {
+                for name in output.prune(&elided) {
+                    debug!(
+                        "eliding {} from the output as it is only referenced by elided containers",
+                        name
+                    );
+                }
+            }
+            let mut output = output.builder_fields(self.builders);
+            let pattern_types = if self.pattern_types {
+                output.pattern_types()
+            } else {
+                vec![]
             };
-            let structs = analyze(schema, kind, cfg)?
-                .rename()
-                .builder_fields(self.builders)
-                .0;
+            let structs = output.0;
+            let validated = self.validated(&structs, &elided);
 
             if !self.hide_prelude {
                 self.print_prelude(&structs);
             }
+            if structs.iter().any(|c| c.uses_go_duration()) {
+                self.print_go_duration();
+            }
+            self.print_format_types(&structs);
+            self.print_schema_types(&structs);
+            if structs.iter().any(|c| c.uses_type("RedactedString")) {
+                self.print_redacted_string();
+            }
+            for (name, pattern) in &pattern_types {
+                self.print_pattern_type(&name.replace(&format!("{}Spec", kind), kind), pattern);
+            }
+            if self.validate_methods() {
+                self.print_validation_error(&structs);
+            }
 
             for s in &structs {
                 if s.level == 0 {
                     continue; // ignoring root struct
                 } else {
-                    if self.elide.contains(&s.name) {
+                    if elided.contains(&s.name) {
                         debug!("eliding {} from the output", s.name);
                         continue;
                     }
                     self.print_docstr(&s.docs, "");
+                    let display_name = display_name(s, kind);
                     if s.is_main_container() {
-                        self.print_derives(s);
+                        self.print_derives(s, &display_name, kind, &structs);
                         //root struct gets kube derives unless opted out
                         if !self.hide_kube {
                             println!(
@@ -244,29 +808,54 @@ impl Kopium {
                                 println!(r#"#[kube(schema = "{}")]"#, self.schema);
                             }
                         }
+                        self.print_attrs(s, &display_name);
                         if s.is_enum {
+                            self.print_non_exhaustive();
                             println!("pub enum {} {{", s.name);
                         } else {
                             println!("pub struct {} {{", s.name);
                         }
                     } else {
-                        self.print_derives(s);
-                        let spec_trimmed_name = s.name.as_str().replace(&format!("{}Spec", kind), kind);
+                        self.print_derives(s, &display_name, kind, &structs);
+                        self.print_attrs(s, &display_name);
                         if s.is_enum {
-                            println!("pub enum {} {{", spec_trimmed_name);
+                            self.print_non_exhaustive();
+                            println!("pub enum {} {{", display_name);
                         } else {
-                            println!("pub struct {} {{", spec_trimmed_name);
+                            println!("pub struct {} {{", display_name);
                         }
                     }
                     for m in &s.members {
                         self.print_docstr(&m.docs, "    ");
-                        if !m.serde_annot.is_empty() {
+                        if !m.serde_annot.is_empty() && !self.enum_value_impls(s) {
                             println!("    #[serde({})]", m.serde_annot.join(", "));
                         }
                         let name = format_ident!("{}", m.name);
                         for annot in &m.extra_annot {
+                            if annot == "#[default]"
+                                && !(self.container_derives(s, &display_name).contains(&"Default")
+                                    && self.can_derive("Default", s, &structs))
+                            {
+                                continue; // only valid with a Default derive
+                            }
                             println!("    {}", annot);
                         }
+                        if let Some(attr) = self.schema_attr(s, m).filter(|_| !self.schema_rules(s)) {
+                            println!("    {}", attr);
+                        }
+                        if !s.is_enum {
+                            for attr in self.constraint_attrs(s, m, &validated) {
+                                println!("    {}", attr);
+                            }
+                            if self.constraints.is_some() {
+                                warn_element_constraints(s, m);
+                            }
+                        }
+                        for attr in &self.attr {
+                            if attr.target.matches_member(s, &display_name, m) {
+                                println!("    {}", attr.value);
+                            }
+                        }
                         let spec_trimmed_type = m.type_.as_str().replace(&format!("{}Spec", kind), kind);
                         if s.is_enum {
                             // NB: only supporting plain enumerations atm, not oneOf
@@ -275,8 +864,37 @@ impl Kopium {
                             println!("    pub {}: {},", name, spec_trimmed_type);
                         }
                     }
+                    if s.is_enum {
+                        self.print_enum_fallback(s);
+                    }
                     println!("}}");
                     println!();
+                    if self.enum_value_impls(s) {
+                        let name = s.name.replace(&format!("{}Spec", kind), kind);
+                        self.print_enum_value_impls(s, &name);
+                    }
+                    if s.is_enum && !s.is_non_string_enum() && self.enum_impls {
+                        let name = s.name.replace(&format!("{}Spec", kind), kind);
+                        self.print_enum_str_impls(s, &name);
+                    }
+                    self.print_schema_with_fns(s, kind);
+                    if self.schema_rules(s) {
+                        self.print_rules_schema(s, &display_name, kind);
+                    }
+                    if matches!(
+                        self.constraints,
+                        Some(ConstraintMode::Validator | ConstraintMode::Generated)
+                    ) {
+                        print_pattern_statics(s);
+                    }
+                    if self.validate_methods() && !s.is_enum {
+                        let name = if s.is_main_container() {
+                            s.name.clone()
+                        } else {
+                            s.name.replace(&format!("{}Spec", kind), kind)
+                        };
+                        self.print_validate_impl(s, &name, &validated);
+                    }
                 }
             }
         } else {
@@ -295,7 +913,7 @@ impl Kopium {
     }
 
     fn completions(&self, shell: clap_complete::Shell) -> Result<()> {
-        let mut command = Self::command();
+        let mut command = Self::cli();
         clap_complete::generate(shell, &mut command, "kopium", &mut std::io::stdout());
         Ok(())
     }
@@ -315,11 +933,15 @@ impl Kopium {
         }
     }
 
-    fn print_derives(&self, s: &Container) {
+    fn print_derives(&self, s: &Container, name: &str, kind: &str, results: &[Container]) {
         let mut derives: Vec<String> = vec!["Serialize", "Deserialize", "Clone", "Debug"]
             .into_iter()
             .map(String::from)
             .collect();
+        if self.enum_value_impls(s) {
+            // serde impls are generated, see print_enum_value_impls
+            derives.retain(|d| d != "Serialize" && d != "Deserialize");
+        }
         if s.is_main_container() && !self.hide_kube {
             // CustomResource first for root struct
             derives.insert(0, "CustomResource".to_string());
@@ -327,67 +949,1581 @@ impl Kopium {
         if self.builders {
             derives.push("TypedBuilder".to_string());
         }
+        if !s.is_enum {
+            match self.constraints {
+                Some(ConstraintMode::Garde) => derives.push("garde::Validate".to_string()),
+                Some(ConstraintMode::Validator) => derives.push("validator::Validate".to_string()),
+                _ => {}
+            }
+        }
         // add user derives last in order
-        for d in &self.derive {
-            if s.is_enum && d == "Default" {
-                // Need to drop Default from enum as this cannot be derived.
-                // Enum defaults need to either be manually derived
-                // or we can insert enum defaults
+        for d in self.container_derives(s, name) {
+            if !self.can_derive(d, s, results) {
+                // Enums can only derive Default with a default variant from the schema
+                if let Some(m) = s
+                    .members
+                    .iter()
+                    .find(|m| !self.type_derives(d, &m.type_, results))
+                {
+                    warn!(
+                        "not deriving {} for {} as member {} of type {} is not known to implement it",
+                        d,
+                        name,
+                        m.name,
+                        m.type_.replace(&format!("{}Spec", kind), kind)
+                    );
+                } else if !(d == "Default" && s.is_enum && !s.has_default_variant()) {
+                    warn!(
+                        "not deriving {} for {} as its fallback variant does not implement it",
+                        d, name
+                    );
+                }
                 continue;
             }
-            derives.push(d.clone());
+            if (self.enum_value_impls(s) || self.schema_rules(s)) && d == "JsonSchema" {
+                continue; // generated with the serde impls, or to add the rules of the struct
+            }
+            derives.push(d.to_string());
         }
         println!("#[derive({})]", derives.join(", "));
     }
 
-    fn has_status_resource(&self, results: &[Container]) -> bool {
-        results
+    /// The overrides from the config file, along with the --use substitutions by JSON path
+    fn path_overrides(&self) -> BTreeMap<String, Override> {
+        let mut overrides = self.overrides.clone();
+        for (path, type_) in self.uses.iter().filter(|(t, _)| !is_container_name(t)) {
+            overrides.entry(path.clone()).or_default().use_ = Some(type_.clone());
+        }
+        overrides
+    }
+
+    /// The user derives targeting a container, in the order they were given
+    fn container_derives(&self, s: &Container, name: &str) -> Vec<&str> {
+        self.derive
             .iter()
-            .any(|o| o.is_status_container() && !o.members.is_empty())
+            .filter(|d| d.target.matches_container(s, name))
+            .map(|d| d.value.as_str())
+            .collect()
     }
 
-    fn print_prelude(&self, results: &[Container]) {
-        println!("#[allow(unused_imports)]");
-        println!("mod prelude {{");
-        if !self.hide_kube {
-            println!("    pub use kube::CustomResource;");
+    /// Whether JsonSchema is derived for all containers
+    ///
+    /// Schema helpers and attributes are only generated in this case.
+    fn derives_json_schema(&self) -> bool {
+        self.derive
+            .iter()
+            .any(|d| matches!(d.target, Target::All) && d.value == "JsonSchema")
+    }
+
+    fn print_attrs(&self, s: &Container, name: &str) {
+        if let Some(o) = self.overrides.get(&s.path) {
+            for attr in &o.attrs {
+                println!("{}", attr);
+            }
         }
-        if self.builders {
-            println!("    pub use typed_builder::TypedBuilder;");
+        for attr in &self.attr {
+            if attr.target.matches_container(s, name) {
+                println!("{}", attr.value);
+            }
         }
-        if self.derive.contains(&"JsonSchema".to_string()) {
-            println!("    pub use schemars::JsonSchema;");
+    }
+
+    /// Whether a derive is valid for a container, given the types of its members
+    ///
+    /// Enums also need a default variant from the schema for Default, and a fallback
+    /// variant with data for the derives of its data.
+    fn can_derive(&self, d: &str, s: &Container, results: &[Container]) -> bool {
+        if s.is_enum {
+            if d == "Default" && !s.has_default_variant() {
+                return false;
+            }
+            return match self.enum_fallback_variant(s) {
+                Some((EnumFallback::Other, _)) if s.is_non_string_enum() => {
+                    self.type_derives(d, "serde_json::Value", results)
+                }
+                Some((EnumFallback::Other, _)) => self.type_derives(d, "String", results),
+                _ => true,
+            };
         }
-        println!("    pub use serde::{{Serialize, Deserialize}};");
-        if results.iter().any(|o| o.uses_btreemaps()) {
-            println!("    pub use std::collections::BTreeMap;");
+        s.members.iter().all(|m| self.type_derives(d, &m.type_, results))
+    }
+
+    /// Whether a member type implements a derivable trait, following containers in results
+    fn type_derives(&self, d: &str, type_: &str, results: &[Container]) -> bool {
+        if let Some(inner) = generic_arg(type_, "Option<") {
+            return d == "Default" || self.type_derives(d, inner, results);
         }
-        if results.iter().any(|o| o.uses_hashmaps()) {
-            println!("    pub use std::collections::HashMap;");
+        if let Some(inner) = generic_arg(type_, "Vec<") {
+            return d == "Default" || (d != "Copy" && self.type_derives(d, inner, results));
         }
-        if results.iter().any(|o| o.uses_datetime()) {
-            println!("    pub use chrono::{{DateTime, Utc}};");
+        if let Some(inner) = generic_arg(type_, "BTreeMap<String, ") {
+            return d == "Default" || (d != "Copy" && self.type_derives(d, inner, results));
         }
-        if results.iter().any(|o| o.uses_date()) {
-            println!("    pub use chrono::naive::NaiveDate;");
+        if let Some(inner) = generic_arg(type_, "HashMap<String, ") {
+            return d == "Default"
+                || (!["Copy", "Hash", "PartialOrd", "Ord"].contains(&d)
+                    && self.type_derives(d, inner, results));
         }
-        if results.iter().any(|o| o.uses_int_or_string()) {
-            println!("    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;");
+        if let Some(c) = results.iter().find(|c| c.name == type_) {
+            return self.can_derive(d, c, results);
         }
-        if results.iter().any(|o| o.contains_conditions()) && !self.no_condition {
-            println!("    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;");
+        let implemented: &[&str] = match type_ {
+            "" | "bool" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => return true,
+            "DateTime<Utc>" | "NaiveDate" | "Timestamp" | "Date" | "GoDuration" | "Uuid" => return true,
+            "f32" | "f64" => &["Default", "PartialEq", "PartialOrd", "Copy"],
+            "Ipv4Addr" | "Ipv6Addr" => &["PartialEq", "Eq", "PartialOrd", "Ord", "Hash", "Copy"],
+            "Url" => &["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+            "serde_json::Value" | "IntOrString" => &["Default", "PartialEq", "Eq"],
+            "Quantity" => &["Default", "PartialEq"],
+            "ByteString" => &["Default", "PartialEq", "Eq", "PartialOrd", "Ord"],
+            "Time" => &["PartialEq", "Eq", "PartialOrd", "Ord"],
+            "Condition" => &["PartialEq"],
+            "String" | "RedactedString" => &["Default", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+            // an empty string is not a valid email or hostname
+            "Email" | "Hostname" => &["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+            // an empty string may not match the pattern
+            _ if self.is_pattern_type(type_, results) => &["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+            // types from --use or the config file are not known to implement anything
+            _ => &[],
+        };
+        !DERIVABLE.contains(&d) || implemented.contains(&d)
+    }
+
+    /// Whether a type is one of the newtypes generated for the pattern of a string member
+    fn is_pattern_type(&self, type_: &str, results: &[Container]) -> bool {
+        self.pattern_types
+            && results.iter().flat_map(|c| &c.members).any(|m| {
+                m.constraints.pattern.is_some()
+                    && (m.type_ == type_ || generic_arg(&m.type_, "Option<") == Some(type_))
+            })
+    }
+
+    fn print_non_exhaustive(&self) {
+        if self.enum_fallback.is_some() {
+            println!("#[non_exhaustive]");
         }
-        println!("}}");
-        println!("use self::prelude::*;\n");
     }
 
-    fn print_generation_warning(&self) {
-        println!("// WARNING: generated by kopium - manual changes will be overwritten");
-        let args = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
-        println!("// kopium command: kopium {}", args);
-        println!("// kopium version: {}", clap::crate_version!());
+    /// The catch-all variant of an enum, named to avoid clashes with its other variants
+    fn enum_fallback_variant(&self, s: &Container) -> Option<(EnumFallback, String)> {
+        let fallback = self.enum_fallback?;
+        let mut name = match fallback {
+            EnumFallback::Unknown => "Unknown".to_string(),
+            EnumFallback::Other => "Other".to_string(),
+        };
+        while s.members.iter().any(|m| m.name == name) {
+            name.push_str("Value");
+        }
+        Some((fallback, name))
+    }
+
+    fn print_enum_fallback(&self, s: &Container) {
+        let Some((fallback, name)) = self.enum_fallback_variant(s) else {
+            return;
+        };
+        if self.enum_value_impls(s) {
+            // handled by the generated serde impls
+            match fallback {
+                EnumFallback::Unknown => println!("    {},", name),
+                EnumFallback::Other if s.is_non_string_enum() => println!("    {}(serde_json::Value),", name),
+                EnumFallback::Other => println!("    {}(String),", name),
+            }
+            return;
+        }
+        if self.derives_json_schema() {
+            println!("    #[schemars(skip)]");
+        }
+        println!("    #[serde(other)]");
+        println!("    {},", name);
+    }
+
+    /// Whether an enum has generated serde impls rather than derived ones
+    ///
+    /// This is needed for values that are not strings, and for unknown strings kept by the fallback variant.
+    fn enum_value_impls(&self, s: &Container) -> bool {
+        s.is_non_string_enum() || (s.is_enum && self.enum_fallback == Some(EnumFallback::Other))
+    }
+
+    /// Conversions between string enums and their values from the schema
+    fn print_enum_str_impls(&self, s: &Container, name: &str) {
+        let fallback = self.enum_fallback_variant(s);
+        let variants = s
+            .members
+            .iter()
+            .map(|m| (format_ident!("{}", m.name), m.original_name()));
+
+        println!("impl {} {{", name);
+        println!("    /// All variants of the enum");
+        println!(
+            "    pub const ALL: &'static [Self] = &[{}];",
+            variants
+                .clone()
+                .map(|(v, _)| format!("Self::{v}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        println!("}}");
+        println!();
+
+        println!("impl AsRef<str> for {} {{", name);
+        println!("    fn as_ref(&self) -> &str {{");
+        println!("        match self {{");
+        for (variant, value) in variants.clone() {
+            println!("            Self::{variant} => {value:?},");
+        }
+        match &fallback {
+            Some((EnumFallback::Unknown, variant)) => {
+                println!(r#"            Self::{variant} => "{variant}","#)
+            }
+            Some((EnumFallback::Other, variant)) => println!("            Self::{variant}(value) => value,"),
+            None => {}
+        }
+        println!("        }}");
+        println!("    }}");
+        println!("}}");
+        println!();
+
+        println!("impl std::fmt::Display for {} {{", name);
+        println!("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{");
+        println!("        f.write_str(self.as_ref())");
+        println!("    }}");
+        println!("}}");
+        println!();
+
+        println!("impl std::str::FromStr for {} {{", name);
+        println!("    type Err = String;");
+        println!();
+        println!("    fn from_str(s: &str) -> Result<Self, Self::Err> {{");
+        println!("        match s {{");
+        for (variant, value) in variants.clone() {
+            println!("            {value:?} => Ok(Self::{variant}),");
+        }
+        match &fallback {
+            Some((EnumFallback::Unknown, variant)) => println!("            _ => Ok(Self::{variant}),"),
+            Some((EnumFallback::Other, variant)) => {
+                println!("            _ => Ok(Self::{variant}(s.to_string())),")
+            }
+            None => {
+                let expected = variants.map(|(_, v)| v).collect::<Vec<_>>().join(", ");
+                println!(
+                    r#"            _ => Err(format!("unknown variant {{s:?}}, expected one of {{}}", {:?})),"#,
+                    expected
+                );
+            }
+        }
+        println!("        }}");
+        println!("    }}");
+        println!("}}");
         println!();
     }
+
+    /// Serde and schemars impls for enums with values that are not strings, or that keep unknown strings
+    fn print_enum_value_impls(&self, s: &Container, name: &str) {
+        let fallback = self.enum_fallback_variant(s);
+        let values = s
+            .members
+            .iter()
+            .filter_map(|m| Some((format_ident!("{}", m.name), value_literal(m.value.as_ref()?))));
+
+        println!("impl Serialize for {} {{", name);
+        println!(
+            "    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{"
+        );
+        println!("        match self {{");
+        for (variant, value) in values.clone() {
+            println!("            Self::{variant} => serde_json::json!({value}).serialize(serializer),");
+        }
+        match &fallback {
+            Some((EnumFallback::Unknown, variant)) => {
+                println!(r#"            Self::{variant} => serializer.serialize_str("{variant}"),"#)
+            }
+            Some((EnumFallback::Other, variant)) => {
+                println!("            Self::{variant}(value) => value.serialize(serializer),")
+            }
+            None => {}
+        }
+        println!("        }}");
+        println!("    }}");
+        println!("}}");
+        println!();
+
+        println!("impl<'de> Deserialize<'de> for {} {{", name);
+        println!(
+            "    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{"
+        );
+        println!("        let value = serde_json::Value::deserialize(deserializer)?;");
+        for (variant, value) in values.clone() {
+            println!("        if value == serde_json::json!({value}) {{");
+            println!("            return Ok(Self::{variant});");
+            println!("        }}");
+        }
+        match &fallback {
+            Some((EnumFallback::Unknown, variant)) => println!("        Ok(Self::{variant})"),
+            Some((EnumFallback::Other, variant)) if s.is_non_string_enum() => {
+                println!("        Ok(Self::{variant}(value))")
+            }
+            Some((EnumFallback::Other, variant)) => println!(
+                "        serde_json::from_value(value).map(Self::{variant}).map_err(serde::de::Error::custom)"
+            ),
+            None => {
+                let expected = values.clone().map(|(_, v)| v).collect::<Vec<_>>().join(", ");
+                println!(
+                    r#"        Err(serde::de::Error::custom(format!("unknown variant {{value}}, expected one of {{}}", {:?})))"#,
+                    expected
+                );
+            }
+        }
+        println!("    }}");
+        println!("}}");
+        println!();
+
+        if !self.derives_json_schema() {
+            return;
+        }
+        let instance_type = enum_instance_type(s);
+        println!("impl JsonSchema for {} {{", name);
+        println!("    fn schema_name() -> String {{");
+        println!(r#"        "{}".to_string()"#, name);
+        println!("    }}");
+        println!();
+        println!(
+            "    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {{"
+        );
+        println!("        schemars::schema::SchemaObject {{");
+        if let Some(instance_type) = instance_type {
+            println!(
+                "            instance_type: Some(schemars::schema::InstanceType::{instance_type}.into()),"
+            );
+        }
+        println!(
+            "            enum_values: Some(vec![{}]),",
+            values
+                .map(|(_, v)| format!("serde_json::json!({v})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        println!("            ..Default::default()");
+        println!("        }}");
+        println!("        .into()");
+        println!("    }}");
+        println!("}}");
+        println!();
+    }
+
+    /// Whether a member needs a schema_with helper to keep its kubernetes extensions
+    fn schema_extensions(&self, s: &Container, m: &Member) -> bool {
+        !s.is_enum && !m.extensions.is_empty() && self.derives_json_schema()
+    }
+
+    /// Whether a member needs a schemars override for a type without a JsonSchema impl
+    fn schema_foreign(&self, s: &Container, m: &Member) -> bool {
+        let foreign = self.schema_types().iter().any(|(t, ..)| m.uses_type(t));
+        !s.is_enum && foreign && self.derives_json_schema()
+    }
+
+    /// Types without a JsonSchema impl, with the generated type describing them and their string format
+    fn schema_types(&self) -> Vec<(&'static str, &'static str, &'static str)> {
+        let mut types = vec![("ByteString", "ByteStringSchema", "byte")];
+        if let TimeType::Jiff = self.time_type {
+            // schemars has no jiff support
+            types.push(("Timestamp", "TimestampSchema", "date-time"));
+            types.push(("Date", "DateSchema", "date"));
+        }
+        types
+    }
+
+    /// The type schemars describes a member with, using the generated schema types of `schema_types`
+    fn schema_type(&self, type_: &str) -> String {
+        let schema_types = self.schema_types();
+        let mut schema_type = String::new();
+        let mut rest = type_;
+        while let Some(start) = rest.find(|c: char| c.is_alphanumeric() || c == '_') {
+            schema_type.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let ident = &rest[..end];
+            match schema_types.iter().find(|(t, ..)| *t == ident) {
+                Some((_, schema, _)) => schema_type.push_str(schema),
+                None => schema_type.push_str(ident),
+            }
+            rest = &rest[end..];
+        }
+        schema_type.push_str(rest);
+        schema_type
+    }
+
+    /// Whether a struct has rules of its own, which are added to its schema by a JsonSchema impl
+    fn schema_rules(&self, s: &Container) -> bool {
+        !s.is_enum && !s.validations.is_empty() && self.derives_json_schema()
+    }
+
+    /// The schemars attribute of a member, for extensions or types without a JsonSchema impl
+    fn schema_attr(&self, s: &Container, m: &Member) -> Option<String> {
+        if self.schema_extensions(s, m) {
+            Some(format!(
+                r#"#[schemars(schema_with = "{}")]"#,
+                schema_with_fn(s, m)
+            ))
+        } else if self.schema_foreign(s, m) {
+            Some(format!(r#"#[schemars(with = "{}")]"#, self.schema_type(&m.type_)))
+        } else {
+            None
+        }
+    }
+
+    /// JsonSchema impl for a struct with x-kubernetes-validations on itself
+    ///
+    /// schemars cannot add extensions to a derived container schema, so the schema is derived
+    /// for a copy of the struct in a private module, and the rules are added to it.
+    fn print_rules_schema(&self, s: &Container, name: &str, kind: &str) {
+        let module = format!("{}_schema", name.to_snake_case());
+        println!("mod {} {{", module);
+        println!("    use super::*;");
+        println!();
+        self.print_docstr(&s.docs, "    ");
+        println!("    #[derive(JsonSchema)]");
+        println!("    #[allow(dead_code)]");
+        println!("    pub struct {} {{", name);
+        for m in &s.members {
+            self.print_docstr(&m.docs, "        ");
+            if !m.serde_annot.is_empty() {
+                println!("        #[serde({})]", m.serde_annot.join(", "));
+            }
+            if let Some(attr) = self.schema_attr(s, m) {
+                println!("        {}", attr);
+            }
+            let type_ = m.type_.replace(&format!("{}Spec", kind), kind);
+            println!("        pub {}: {},", format_ident!("{}", m.name), type_);
+        }
+        println!("    }}");
+        println!("}}");
+        println!();
+        println!("impl JsonSchema for {} {{", name);
+        println!("    fn schema_name() -> String {{");
+        println!(r#"        "{}".to_string()"#, name);
+        println!("    }}");
+        println!();
+        println!(
+            "    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {{"
+        );
+        println!(
+            "        let mut schema = <{}::{} as JsonSchema>::json_schema(gen).into_object();",
+            module, name
+        );
+        println!(
+            r#"        schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!({}));"#,
+            serde_json::json!(s.validations)
+        );
+        println!("        schema.into()");
+        println!("    }}");
+        println!("}}");
+        println!();
+    }
+
+    fn print_schema_with_fns(&self, s: &Container, kind: &str) {
+        for m in &s.members {
+            if !self.schema_extensions(s, m) {
+                continue;
+            }
+            // optional members are not required through serde(default), schemars needs the inner type
+            let type_ = unwrap_option(&m.type_).replace(&format!("{}Spec", kind), kind);
+            println!(
+                "fn {}(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {{",
+                schema_with_fn(s, m)
+            );
+            println!(
+                "    let mut schema = <{} as JsonSchema>::json_schema(gen).into_object();",
+                self.schema_type(&type_)
+            );
+            for (key, value) in &m.extensions {
+                println!(
+                    r#"    schema.extensions.insert("{}".to_string(), serde_json::json!({}));"#,
+                    key, value
+                );
+            }
+            println!("    schema.into()");
+            println!("}}");
+            println!();
+        }
+    }
+
+    /// Whether structs get generated validate methods
+    fn validate_methods(&self) -> bool {
+        self.cel_validation || self.constraints == Some(ConstraintMode::Generated)
+    }
+
+    /// Whether a container is elided from the output
+    fn elides(&self, s: &Container, kind: &str) -> bool {
+        s.level > 0 && self.elide.iter().any(|e| e.matches(s, &display_name(s, kind)))
+    }
+
+    /// Structs that derive or generate validation, and can thus be recursed into
+    fn validated<'a>(&self, results: &'a [Container], elided: &[String]) -> Vec<&'a str> {
+        results
+            .iter()
+            .filter(|c| c.level > 0 && !c.is_enum && !elided.contains(&c.name))
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    /// Field attributes for the garde or validator crates
+    fn constraint_attrs(&self, s: &Container, m: &Member, validated: &[&str]) -> Vec<String> {
+        let mode = match self.constraints {
+            Some(ConstraintMode::Garde) => "garde",
+            Some(ConstraintMode::Validator) => "validate",
+            _ => return vec![],
+        };
+        let c = &m.constraints;
+        let inner = unwrap_option(&m.type_);
+        let mut rules = vec![];
+        if inner == "String" {
+            if let Some(pattern) = &c.pattern {
+                if mode == "garde" {
+                    rules.push(format!("pattern({})", raw_string(pattern)));
+                } else {
+                    rules.push(format!("regex(path = *{})", pattern_static(s, m)));
+                }
+            }
+            if c.min_length.is_some() || c.max_length.is_some() {
+                let chars = if mode == "garde" {
+                    Some("chars".to_string())
+                } else {
+                    None
+                };
+                let bounds = [("min", c.min_length), ("max", c.max_length)]
+                    .into_iter()
+                    .filter_map(|(k, v)| v.map(|v| format!("{k} = {v}")));
+                rules.push(format!(
+                    "length({})",
+                    chars.into_iter().chain(bounds).collect::<Vec<_>>().join(", ")
+                ));
+            }
+        }
+        if is_number(inner) && (c.minimum.is_some() || c.maximum.is_some()) {
+            let mut bounds = vec![];
+            for (bound, value, exclusive, step) in [
+                ("min", c.minimum, c.exclusive_minimum, 1.0),
+                ("max", c.maximum, c.exclusive_maximum, -1.0),
+            ] {
+                let Some(value) = value else { continue };
+                let literal = |v: f64| {
+                    if inner.starts_with('f') {
+                        format!("{v:?}")
+                    } else if step > 0.0 {
+                        format!("{}", v.ceil() as i64)
+                    } else {
+                        format!("{}", v.floor() as i64)
+                    }
+                };
+                if !exclusive {
+                    bounds.push(format!("{bound} = {}", literal(value)));
+                } else if mode == "validate" {
+                    bounds.push(format!("exclusive_{bound} = {}", literal(value)));
+                } else if inner.starts_with('f') {
+                    warn!(
+                        "garde has no exclusive {bound} for {}.{}, using inclusive bound",
+                        s.name, m.name
+                    );
+                    bounds.push(format!("{bound} = {}", literal(value)));
+                } else {
+                    bounds.push(format!("{bound} = {}", literal(value + step)));
+                }
+            }
+            rules.push(format!("range({})", bounds.join(", ")));
+        }
+        let items = (c.min_items, c.max_items);
+        let props = (c.min_properties, c.max_properties);
+        let counts = if inner.starts_with("Vec<") { items } else { props };
+        if counts.0.is_some() || counts.1.is_some() {
+            let bounds = [("min", counts.0), ("max", counts.1)]
+                .into_iter()
+                .filter_map(|(k, v)| v.map(|v| format!("{k} = {v}")))
+                .collect::<Vec<_>>();
+            rules.push(format!("length({})", bounds.join(", ")));
+        }
+        if c.unique_items || !c.required.is_empty() {
+            warn!(
+                "uniqueItems and required map keys on {}.{} are not supported by {mode}",
+                s.name, m.name
+            );
+        }
+        if validated.contains(&innermost_type(&m.type_)) {
+            rules.push(if mode == "garde" { "dive" } else { "nested" }.to_string());
+        }
+        if rules.is_empty() && mode == "garde" {
+            // garde requires every field to declare its rules
+            rules.push("skip".to_string());
+        }
+        if rules.is_empty() {
+            vec![]
+        } else {
+            vec![format!("#[{mode}({})]", rules.join(", "))]
+        }
+    }
+
+    fn print_validation_error(&self, results: &[Container]) {
+        println!("/// A validation rule from the schema that did not hold");
+        println!("#[derive(Clone, Debug, PartialEq)]");
+        println!("pub struct ValidationError {{");
+        println!("    /// Path to the field the rule applies to");
+        println!("    pub field: String,");
+        println!("    /// The CEL rule or schema constraint that failed");
+        println!("    pub rule: String,");
+        println!("    /// The message of the rule, or the reason it could not be evaluated");
+        println!("    pub message: String,");
+        println!("}}");
+        println!();
+        let printed = results.iter().filter(|c| c.level > 0 && !c.is_enum);
+        if self.constraints == Some(ConstraintMode::Generated) {
+            let constraints = printed.clone().flat_map(|c| &c.members).map(|m| &m.constraints);
+            if constraints.clone().any(|c| !c.is_empty()) {
+                println!("fn validate_constraint(holds: bool, field: &str, rule: &str, message: &str, errors: &mut Vec<ValidationError>) {{");
+                println!("    if !holds {{");
+                println!("        errors.push(ValidationError {{");
+                println!("            field: field.to_string(),");
+                println!("            rule: rule.to_string(),");
+                println!("            message: message.to_string(),");
+                println!("        }});");
+                println!("    }}");
+                println!("}}");
+                println!();
+            }
+            if constraints.clone().any(|c| c.unique_items) {
+                println!("fn all_unique<T: Serialize>(values: &[T]) -> bool {{");
+                println!(
+                    "    let values: Vec<_> = values.iter().map(|v| serde_json::to_value(v).ok()).collect();"
+                );
+                println!("    values.iter().enumerate().all(|(i, v)| !values[..i].contains(v))");
+                println!("}}");
+                println!();
+            }
+        }
+        if !self.cel_validation || !printed.clone().any(|c| c.has_validations()) {
+            return;
+        }
+        println!("fn validate_cel<T: Serialize>(program: &Result<cel_interpreter::Program, String>, value: &T, field: &str, rule: &str, message: &str, errors: &mut Vec<ValidationError>) {{");
+        println!("    let result = program.as_ref().map_err(|e| e.clone()).and_then(|program| {{");
+        println!("        let mut context = cel_interpreter::Context::default();");
+        println!(r#"        context.add_variable("self", value).map_err(|e| e.to_string())?;"#);
+        println!("        program.execute(&context).map_err(|e| e.to_string())");
+        println!("    }});");
+        println!("    let message = match result {{");
+        println!("        Ok(cel_interpreter::Value::Bool(true)) => return,");
+        println!("        Ok(_) => message.to_string(),");
+        println!(r#"        Err(e) => format!("{{message}}: {{e}}"),"#);
+        println!("    }};");
+        println!("    errors.push(ValidationError {{");
+        println!("        field: field.to_string(),");
+        println!("        rule: rule.to_string(),");
+        println!("        message,");
+        println!("    }});");
+        println!("}}");
+        println!();
+    }
+
+    fn print_validate_impl(&self, s: &Container, name: &str, validated: &[&str]) {
+        let mut body = vec![];
+        // rules are compiled once, into statics named by rule_static
+        let mut programs = vec![];
+        if self.cel_validation {
+            for (i, rule) in evaluated_rules(&s.validations).enumerate() {
+                let program = rule_static(s, None, i);
+                body.push(validate_cel_call(&program, "self", "{path}", rule));
+                programs.push((program, rule.rule.clone()));
+            }
+        }
+        for m in &s.members {
+            let field = m.name.as_str();
+            let field_path = format!("{{path}}.{}", m.original_name());
+            let path = format!(r#"&format!("{field_path}")"#);
+            let inner = unwrap_option(&m.type_);
+            let optional = m.type_.starts_with("Option<");
+            let value = if optional {
+                "value".to_string()
+            } else {
+                format!("&self.{field}")
+            };
+            let mut calls = vec![];
+            // rules on struct members are found on the container the member points to
+            if self.cel_validation && !validated.contains(&inner) {
+                let rules = m.validations();
+                for (i, rule) in evaluated_rules(&rules).enumerate() {
+                    let program = rule_static(s, Some(m), i);
+                    calls.push(validate_cel_call(&program, &value, &field_path, rule));
+                    programs.push((program, rule.rule.clone()));
+                }
+            }
+            if self.constraints == Some(ConstraintMode::Generated) {
+                calls.extend(constraint_checks(s, m, &value, &field_path));
+            }
+            if !calls.is_empty() && optional {
+                // rules only apply to fields that are set
+                body.push(format!("if let Some(value) = &self.{field} {{"));
+                body.extend(calls);
+                body.push("}".to_string());
+            } else {
+                body.extend(calls);
+            }
+            body.extend(validate_recursion(
+                &m.type_,
+                &format!("self.{field}"),
+                &path,
+                validated,
+                0,
+            ));
+        }
+
+        let (path, errors) = if body.is_empty() {
+            ("_path", "_errors")
+        } else {
+            ("path", "errors")
+        };
+        for (program, rule) in programs {
+            println!(
+                "static {program}: std::sync::LazyLock<Result<cel_interpreter::Program, String>> = std::sync::LazyLock::new(|| cel_interpreter::Program::compile({rule:?}).map_err(|e| e.to_string()));"
+            );
+            println!();
+        }
+        println!("impl {} {{", name);
+        println!("    /// Evaluate the validation rules from the schema");
+        println!("    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {{");
+        println!("        let mut errors = vec![];");
+        println!(r#"        self.validate_at("", &mut errors);"#);
+        println!("        if errors.is_empty() {{");
+        println!("            Ok(())");
+        println!("        }} else {{");
+        println!("            Err(errors)");
+        println!("        }}");
+        println!("    }}");
+        println!();
+        println!("    fn validate_at(&self, {path}: &str, {errors}: &mut Vec<ValidationError>) {{");
+        let mut indent = 2;
+        for line in body {
+            if line.starts_with('}') {
+                indent -= 1;
+            }
+            println!("{}{}", "    ".repeat(indent), line);
+            if line.ends_with('{') {
+                indent += 1;
+            }
+        }
+        println!("    }}");
+        println!("}}");
+        println!();
+    }
+
+    fn has_status_resource(&self, results: &[Container]) -> bool {
+        results
+            .iter()
+            .any(|o| o.is_status_container() && !o.members.is_empty())
+    }
+
+    fn print_prelude(&self, results: &[Container]) {
+        let mut imports: Vec<&str> = vec![];
+        if !self.hide_kube {
+            imports.push("kube::CustomResource");
+        }
+        if self.builders {
+            imports.push("typed_builder::TypedBuilder");
+        }
+        if self.derive.iter().any(|d| d.value == "JsonSchema") {
+            imports.push("schemars::JsonSchema");
+        }
+        imports.push("serde::{Serialize, Deserialize}");
+        if results.iter().any(|o| o.uses_btreemaps()) {
+            imports.push("std::collections::BTreeMap");
+        }
+        if results.iter().any(|o| o.uses_hashmaps()) {
+            imports.push("std::collections::HashMap");
+        }
+        if let Some(import) = self.time_type.date_time_import() {
+            if results.iter().any(|o| o.uses_datetime(self.time_type)) {
+                imports.push(import);
+            }
+        }
+        if let Some(import) = self.time_type.date_import() {
+            if results.iter().any(|o| o.uses_date(self.time_type)) {
+                imports.push(import);
+            }
+        }
+        for (type_, import) in [
+            ("Uuid", "uuid::Uuid"),
+            ("Url", "url::Url"),
+            ("Ipv4Addr", "std::net::Ipv4Addr"),
+            ("Ipv6Addr", "std::net::Ipv6Addr"),
+        ] {
+            if results.iter().any(|o| o.uses_type(type_)) {
+                imports.push(import);
+            }
+        }
+        if results.iter().any(|o| o.uses_type("ByteString")) {
+            imports.push("k8s_openapi::ByteString");
+        }
+        if results.iter().any(|o| o.uses_int_or_string()) {
+            imports.push("k8s_openapi::apimachinery::pkg::util::intstr::IntOrString");
+        }
+        if results.iter().any(|o| o.uses_quantity()) {
+            imports.push("k8s_openapi::apimachinery::pkg::api::resource::Quantity");
+        }
+        if results.iter().any(|o| o.contains_conditions()) && !self.no_condition {
+            imports.push("k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition");
+        }
+        if self.constraints == Some(ConstraintMode::Validator) {
+            // the nested rules call validate on the members
+            imports.push("validator::Validate");
+        }
+        // user imports may already be needed for the generated code
+        for import in &self.import {
+            if !imports.contains(&import.as_str()) {
+                imports.push(import);
+            }
+        }
+        println!("#[allow(unused_imports)]");
+        println!("mod prelude {{");
+        for import in imports {
+            println!("    pub use {};", import);
+        }
+        println!("}}");
+        println!("use self::prelude::*;\n");
+    }
+
+    fn print_go_duration(&self) {
+        print!("{}", GO_DURATION);
+        if self.derives_json_schema() {
+            print!("{}", string_schema("GoDuration", None));
+        }
+    }
+
+    fn print_redacted_string(&self) {
+        print!("{}", REDACTED_STRING);
+        if self.derives_json_schema() {
+            print!("{}", string_schema("RedactedString", Some("password")));
+        }
+    }
+
+    fn print_pattern_type(&self, name: &str, pattern: &str) {
+        print!(
+            "{}",
+            PATTERN_TYPE
+                .replace("NAME", name)
+                .replace("PATTERN_LITERAL", &raw_string(pattern))
+        );
+        if self.derives_json_schema() {
+            print!("{}", PATTERN_TYPE_SCHEMA.replace("NAME", name));
+        }
+    }
+
+    /// Validated newtypes for the email and hostname formats
+    fn print_format_types(&self, results: &[Container]) {
+        let email = results.iter().any(|c| c.uses_type("Email"));
+        let hostname = results.iter().any(|c| c.uses_type("Hostname"));
+        if !email && !hostname {
+            return;
+        }
+        print!("{}", IS_HOSTNAME);
+        for (used, name, format, check) in [
+            (email, "Email", "email", IS_EMAIL),
+            (hostname, "Hostname", "hostname", ""),
+        ] {
+            if !used {
+                continue;
+            }
+            print!("{}", check);
+            print!(
+                "{}",
+                STRING_NEWTYPE.replace("NAME", name).replace("FORMAT", format)
+            );
+            if self.derives_json_schema() {
+                print!("{}", string_schema(name, Some(format)));
+            }
+        }
+    }
+
+    /// Uninhabited types describing the members of `schema_types` to schemars
+    fn print_schema_types(&self, results: &[Container]) {
+        if !self.derives_json_schema() {
+            return;
+        }
+        for (type_, schema, format) in self.schema_types() {
+            if results.iter().any(|c| c.uses_type(type_)) {
+                println!("/// Schema of {type_}, which has no JsonSchema impl");
+                println!("enum {schema} {{}}");
+                println!();
+                print!("{}", string_schema(schema, Some(format)));
+            }
+        }
+    }
+
+    fn print_generation_warning(&self) {
+        println!("// WARNING: generated by kopium - manual changes will be overwritten");
+        let args = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+        println!("// kopium command: kopium {}", args);
+        println!("// kopium version: {}", clap::crate_version!());
+        println!();
+    }
+}
+
+/// Duration newtype for strings in the go time.Duration format
+const GO_DURATION: &str = r#"/// A duration in the go format, such as "1h30m", "2.5s" or "250ms"
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GoDuration(pub std::time::Duration);
+
+impl From<std::time::Duration> for GoDuration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<GoDuration> for std::time::Duration {
+    fn from(duration: GoDuration) -> Self {
+        duration.0
+    }
+}
+
+impl std::str::FromStr for GoDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid duration {s:?}");
+        let mut rest = s.strip_prefix('+').unwrap_or(s);
+        if rest == "0" {
+            return Ok(Self::default());
+        }
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+        let mut nanos: u128 = 0;
+        while !rest.is_empty() {
+            let split = rest.find(|c: char| !c.is_ascii_digit() && c != '.').ok_or_else(invalid)?;
+            let (number, tail) = rest.split_at(split);
+            let split = tail.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(split);
+            let scale: u128 = match unit {
+                "ns" => 1,
+                "us" | "µs" | "μs" => 1_000,
+                "ms" => 1_000_000,
+                "s" => 1_000_000_000,
+                "m" => 60_000_000_000,
+                "h" => 3_600_000_000_000,
+                "d" => 86_400_000_000_000,
+                "w" => 604_800_000_000_000,
+                "y" => 31_536_000_000_000_000,
+                _ => return Err(invalid()),
+            };
+            let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+            if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+                return Err(invalid());
+            }
+            let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+            let mut value = whole.checked_mul(scale).ok_or_else(invalid)?;
+            let mut divisor = 1;
+            for digit in fraction.bytes().take(18) {
+                divisor *= 10;
+                value += u128::from(digit - b'0') * scale / divisor;
+            }
+            nanos = nanos.checked_add(value).ok_or_else(invalid)?;
+            rest = tail;
+        }
+        let nanos = u64::try_from(nanos).map_err(|_| invalid())?;
+        Ok(Self(std::time::Duration::from_nanos(nanos)))
+    }
+}
+
+impl std::fmt::Display for GoDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn decimal(value: u128, scale: u128) -> String {
+            let (whole, fraction) = (value / scale, value % scale);
+            if fraction == 0 {
+                return whole.to_string();
+            }
+            let width = scale.ilog10() as usize;
+            format!("{whole}.{fraction:0width$}").trim_end_matches('0').to_string()
+        }
+        let nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+        if nanos < 1_000 {
+            return write!(f, "{nanos}ns");
+        }
+        if nanos < 1_000_000 {
+            return write!(f, "{}µs", decimal(nanos, 1_000));
+        }
+        if nanos < 1_000_000_000 {
+            return write!(f, "{}ms", decimal(nanos, 1_000_000));
+        }
+        let hours = nanos / 3_600_000_000_000;
+        let minutes = nanos / 60_000_000_000 % 60;
+        if hours > 0 {
+            write!(f, "{hours}h")?;
+        }
+        if hours > 0 || minutes > 0 {
+            write!(f, "{minutes}m")?;
+        }
+        write!(f, "{}s", decimal(nanos % 60_000_000_000, 1_000_000_000))
+    }
+}
+
+impl Serialize for GoDuration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GoDuration {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+"#;
+
+/// Secret string newtype that (de)serializes as a plain string, but is never printed by Debug
+const REDACTED_STRING: &str = r#"/// A secret string that is redacted from Debug output
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RedactedString(String);
+
+impl RedactedString {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// The secret value
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for RedactedString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl std::fmt::Debug for RedactedString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+"#;
+
+/// A string newtype that is checked against a pattern from the schema when created
+const PATTERN_TYPE: &str = r#"/// A string matching the pattern of the NAME property
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NAME(String);
+
+impl NAME {
+    pub const PATTERN: &'static str = PATTERN_LITERAL;
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for NAME {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        static PATTERN: std::sync::LazyLock<regex::Regex> =
+            std::sync::LazyLock::new(|| regex::Regex::new(NAME::PATTERN).unwrap());
+        if PATTERN.is_match(&value) {
+            Ok(Self(value))
+        } else {
+            Err(format!("{value:?} does not match the pattern {}", Self::PATTERN))
+        }
+    }
+}
+
+impl std::str::FromStr for NAME {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl std::ops::Deref for NAME {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for NAME {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for NAME {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::try_from(s).map_err(serde::de::Error::custom)
+    }
+}
+
+"#;
+
+const PATTERN_TYPE_SCHEMA: &str = r#"impl JsonSchema for NAME {
+    fn schema_name() -> String {
+        "NAME".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.string().pattern = Some(Self::PATTERN.to_string());
+        schema.into()
+    }
+}
+
+"#;
+
+/// Schema of a newtype serialized as a string
+fn string_schema(name: &str, format: Option<&str>) -> String {
+    let schema = match format {
+        Some(f) => format!(
+            r#"let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("{f}".to_string());
+        schema.into()"#
+        ),
+        None => "<String as JsonSchema>::json_schema(gen)".to_string(),
+    };
+    format!(
+        r#"impl JsonSchema for {name} {{
+    fn schema_name() -> String {{
+        "{name}".to_string()
+    }}
+
+    fn is_referenceable() -> bool {{
+        false
+    }}
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {{
+        {schema}
+    }}
+}}
+
+"#
+    )
+}
+
+/// A string newtype that is checked by an `is_FORMAT` fn when parsed or deserialized
+const STRING_NEWTYPE: &str = r#"/// A string with the FORMAT format
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NAME(String);
+
+impl NAME {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for NAME {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for NAME {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for NAME {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_FORMAT(s) {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(format!("invalid FORMAT {s:?}"))
+        }
+    }
+}
+
+impl Serialize for NAME {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for NAME {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+"#;
+
+/// RFC 1123 hostnames, as accepted by kubernetes
+const IS_HOSTNAME: &str = r#"fn is_hostname(s: &str) -> bool {
+    s.len() <= 253
+        && s.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+"#;
+
+const IS_EMAIL: &str = r#"fn is_email(s: &str) -> bool {
+    s.rsplit_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty()
+            && local.len() <= 64
+            && !local.contains(|c: char| c.is_whitespace() || c == '@')
+            && is_hostname(domain)
+    })
+}
+
+"#;
+
+/// Lines checking schema constraints on the `value` of a member, which is a reference
+fn constraint_checks(s: &Container, m: &Member, value: &str, path: &str) -> Vec<String> {
+    let c = &m.constraints;
+    let inner = unwrap_option(&m.type_);
+    // method calls bind tighter than &, so fields are called on without it
+    let receiver = match value.strip_prefix('&') {
+        Some(field) => field,
+        None => value,
+    };
+    let mut checks = vec![];
+    let mut check = |holds: String, rule: String, message: String| {
+        checks.push(format!(
+            r#"validate_constraint({holds}, &format!("{path}"), {rule:?}, {message:?}, errors);"#
+        ));
+    };
+    if inner == "String" {
+        if let Some(pattern) = &c.pattern {
+            check(
+                format!("{}.is_match({value})", pattern_static(s, m)),
+                format!("pattern: {pattern}"),
+                format!("must match the pattern {pattern}"),
+            );
+        }
+        if let Some(min) = c.min_length {
+            check(
+                format!("{receiver}.chars().count() >= {min}"),
+                format!("minLength: {min}"),
+                format!("must be at least {min} characters long"),
+            );
+        }
+        if let Some(max) = c.max_length {
+            check(
+                format!("{receiver}.chars().count() <= {max}"),
+                format!("maxLength: {max}"),
+                format!("must be at most {max} characters long"),
+            );
+        }
+    }
+    if is_number(inner) {
+        let value = match value.strip_prefix('&') {
+            Some(field) => field.to_string(),
+            None => format!("*{value}"),
+        };
+        if let Some(min) = c.minimum {
+            let (op, rule) = if c.exclusive_minimum {
+                (">", "exclusiveMinimum")
+            } else {
+                (">=", "minimum")
+            };
+            check(
+                format!("{value} as f64 {op} {min:?}"),
+                format!("{rule}: {min}"),
+                format!("must be {op} {min}"),
+            );
+        }
+        if let Some(max) = c.maximum {
+            let (op, rule) = if c.exclusive_maximum {
+                ("<", "exclusiveMaximum")
+            } else {
+                ("<=", "maximum")
+            };
+            check(
+                format!("{value} as f64 {op} {max:?}"),
+                format!("{rule}: {max}"),
+                format!("must be {op} {max}"),
+            );
+        }
+    }
+    if inner.starts_with("Vec<") {
+        if let Some(min) = c.min_items {
+            check(
+                format!("{receiver}.len() >= {min}"),
+                format!("minItems: {min}"),
+                format!("must have at least {min} items"),
+            );
+        }
+        if let Some(max) = c.max_items {
+            check(
+                format!("{receiver}.len() <= {max}"),
+                format!("maxItems: {max}"),
+                format!("must have at most {max} items"),
+            );
+        }
+        if c.unique_items {
+            check(
+                format!("all_unique({value})"),
+                "uniqueItems: true".to_string(),
+                "must only have unique items".to_string(),
+            );
+        }
+    }
+    if inner.starts_with("BTreeMap<") || inner.starts_with("HashMap<") {
+        if let Some(min) = c.min_properties {
+            check(
+                format!("{receiver}.len() >= {min}"),
+                format!("minProperties: {min}"),
+                format!("must have at least {min} properties"),
+            );
+        }
+        if let Some(max) = c.max_properties {
+            check(
+                format!("{receiver}.len() <= {max}"),
+                format!("maxProperties: {max}"),
+                format!("must have at most {max} properties"),
+            );
+        }
+        for key in &c.required {
+            check(
+                format!("{receiver}.contains_key({key:?})"),
+                format!("required: {key}"),
+                format!("must have the property {key}"),
+            );
+        }
+    }
+    checks
+}
+
+/// Warn about constraints on the items or values of a member, which are only checked on members themselves
+fn warn_element_constraints(s: &Container, m: &Member) {
+    let Some(c) = &m.constraints.elements else {
+        return;
+    };
+    let inner = innermost_type(&m.type_);
+    let unchecked = match inner {
+        "String" => c.pattern.is_some() || c.min_length.is_some() || c.max_length.is_some(),
+        _ if is_number(inner) => c.minimum.is_some() || c.maximum.is_some(),
+        _ => false,
+    };
+    if unchecked {
+        warn!(
+            "constraints on the items or values of {}.{} are not checked",
+            s.name, m.name
+        );
+    }
+}
+
+/// Statics with the compiled patterns of string members, for validator regex attributes and constraint checks
+fn print_pattern_statics(s: &Container) {
+    for m in &s.members {
+        if let (Some(pattern), "String") = (&m.constraints.pattern, unwrap_option(&m.type_)) {
+            println!(
+                "static {}: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| regex::Regex::new({}).unwrap());",
+                pattern_static(s, m),
+                raw_string(pattern)
+            );
+            println!();
+        }
+    }
+}
+
+fn pattern_static(s: &Container, m: &Member) -> String {
+    format!(
+        "{}_{}_PATTERN",
+        s.name.to_shouty_snake_case(),
+        m.name.trim_start_matches("r#").to_shouty_snake_case()
+    )
+}
+
+/// A raw string literal for a pattern, picking enough hashes to allow quotes in the pattern
+fn raw_string(value: &str) -> String {
+    let mut hashes = String::new();
+    while value.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{value}\"{hashes}")
+}
+
+fn is_number(type_: &str) -> bool {
+    [
+        "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128", "f32", "f64",
+    ]
+    .contains(&type_)
+}
+
+fn unwrap_option(type_: &str) -> &str {
+    type_
+        .strip_prefix("Option<")
+        .and_then(|t| t.strip_suffix('>'))
+        .unwrap_or(type_)
+}
+
+/// The type inside of any Option, Vec, or map wrappers
+fn innermost_type(type_: &str) -> &str {
+    let mut type_ = type_;
+    loop {
+        let inner = ["Option<", "Vec<", "BTreeMap<String, ", "HashMap<String, "]
+            .iter()
+            .find_map(|w| type_.strip_prefix(w).and_then(|t| t.strip_suffix('>')));
+        match inner {
+            Some(inner) => type_ = inner,
+            None => return type_,
+        }
+    }
+}
+
+/// Rules that can be evaluated without access to the previous version of the object
+fn evaluated_rules(rules: &[ValidationRule]) -> impl Iterator<Item = &ValidationRule> {
+    rules.iter().filter(|r| {
+        if r.rule.contains("oldSelf") {
+            debug!("skipping transition rule {}", r.rule);
+        }
+        !r.rule.contains("oldSelf")
+    })
+}
+
+/// Name of the static with the compiled program of the `i`th evaluated rule of a struct or one of its members
+fn rule_static(s: &Container, m: Option<&Member>, i: usize) -> String {
+    match m {
+        Some(m) => format!(
+            "{}_{}_RULE_{i}",
+            s.name.to_shouty_snake_case(),
+            m.name.trim_start_matches("r#").to_shouty_snake_case()
+        ),
+        None => format!("{}_RULE_{i}", s.name.to_shouty_snake_case()),
+    }
+}
+
+/// Call to the generated validate_cel fn for a rule, where `path` is the format string of the field path
+fn validate_cel_call(program: &str, value: &str, path: &str, rule: &ValidationRule) -> String {
+    let field = match &rule.field_path {
+        Some(fp) => format!(r#"&format!("{path}{fp}")"#),
+        None if path == "{path}" => "path".to_string(),
+        None => format!(r#"&format!("{path}")"#),
+    };
+    let message = match &rule.message {
+        Some(msg) => msg.clone(),
+        None => format!("failed rule: {}", rule.rule),
+    };
+    format!(
+        "validate_cel(&{program}, {value}, {field}, {:?}, {:?}, errors);",
+        rule.rule, message
+    )
+}
+
+/// Lines calling validate_at on all generated structs reachable through a member of type `type_`
+///
+/// The member `value` is a field or a reference, and is only used as the receiver of method calls.
+fn validate_recursion(type_: &str, value: &str, path: &str, validated: &[&str], depth: usize) -> Vec<String> {
+    // indices are numbered like the values, as the paths of nested elements use all of them
+    let (x, i, k) = (format!("x{depth}"), format!("i{depth}"), format!("k{depth}"));
+    let inner_lines = |inner: &str, path: &str| validate_recursion(inner, &x, path, validated, depth + 1);
+    let mut lines = vec![];
+    if let Some(inner) = type_.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        let inner = inner_lines(inner, path);
+        if !inner.is_empty() {
+            lines.push(format!("if let Some({x}) = {value}.as_ref() {{"));
+            lines.extend(inner);
+            lines.push("}".to_string());
+        }
+    } else if let Some(inner) = type_.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+        let inner = inner_lines(inner, &format!(r#"&format!("{{path}}[{{{i}}}]")"#));
+        if !inner.is_empty() {
+            lines.push(format!("for ({i}, {x}) in {value}.iter().enumerate() {{"));
+            lines.push(format!("let path = {path};"));
+            lines.extend(inner);
+            lines.push("}".to_string());
+        }
+    } else if let Some(inner) = ["BTreeMap<String, ", "HashMap<String, "]
+        .iter()
+        .find_map(|map| type_.strip_prefix(map).and_then(|t| t.strip_suffix('>')))
+    {
+        let inner = inner_lines(inner, &format!(r#"&format!("{{path}}[{{{k}}}]")"#));
+        if !inner.is_empty() {
+            lines.push(format!("for ({k}, {x}) in {value}.iter() {{"));
+            lines.push(format!("let path = {path};"));
+            lines.extend(inner);
+            lines.push("}".to_string());
+        }
+    } else if validated.contains(&type_) {
+        lines.push(format!("{value}.validate_at({path}, errors);"));
+    }
+    lines
+}
+
+/// The name a container is printed with, which drops the Spec of the kind for nested containers
+fn display_name(s: &Container, kind: &str) -> String {
+    if s.is_main_container() {
+        s.name.clone()
+    } else {
+        s.name.replace(&format!("{}Spec", kind), kind)
+    }
+}
+
+/// Whether a --use target is a generated container name rather than a JSON path
+///
+/// Container names are PascalCase, while JSON paths start with a property such as spec.
+fn is_container_name(target: &str) -> bool {
+    target.starts_with(|c: char| c.is_ascii_uppercase()) && !target.contains('.')
+}
+
+/// The derives that depend on the member types of a container
+const DERIVABLE: [&str; 7] = ["Copy", "Default", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash"];
+
+/// The type argument of a generic type, such as `T` for `Vec<T>` with the prefix `Vec<`
+fn generic_arg<'a>(type_: &'a str, prefix: &str) -> Option<&'a str> {
+    type_.strip_prefix(prefix)?.strip_suffix('>')
+}
+
+/// A rust literal for an enum value, usable in the serde_json::json macro
+fn value_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => format!("{s:?}"),
+        v => v.to_string(),
+    }
+}
+
+/// The schemars InstanceType shared by all values of an enum
+fn enum_instance_type(s: &Container) -> Option<&'static str> {
+    let mut types = s.members.iter().filter_map(|m| {
+        Some(match m.value.as_ref()? {
+            serde_json::Value::Bool(_) => "Boolean",
+            serde_json::Value::Number(n) if n.is_f64() => "Number",
+            serde_json::Value::Number(_) => "Integer",
+            serde_json::Value::String(_) => "String",
+            _ => "Null",
+        })
+    });
+    let first = types.next()?;
+    types.try_fold(first, |shared, t| match (shared, t) {
+        _ if shared == t => Some(shared),
+        ("Integer" | "Number", "Integer" | "Number") => Some("Number"),
+        _ => None,
+    })
+}
+
+/// Name of the generated schemars helper fn for a member with kubernetes extensions
+fn schema_with_fn(s: &Container, m: &Member) -> String {
+    format!(
+        "{}_{}_schema",
+        s.name.to_snake_case(),
+        m.name.trim_start_matches("r#")
+    )
 }
 
 fn find_crd_version<'a>(
@@ -1,21 +1,57 @@
+use crate::analyzer::child_path;
 use heck::{ToPascalCase, ToSnakeCase};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::ValidationRule;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// All found containers
 pub struct Output(pub Vec<Container>);
 
+/// Names recorded from a previous run, so regenerating keeps them stable
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lock {
+    /// Container names by JSON path, such as `spec.endpoints`
+    pub containers: BTreeMap<String, String>,
+    /// Member and variant names by the JSON path of their container and their name in the schema
+    pub members: BTreeMap<String, String>,
+}
+
+impl Lock {
+    /// The names of the containers and members in an output
+    pub fn from_output(output: &Output) -> Self {
+        let mut lock = Self::default();
+        for c in output.0.iter().filter(|c| !c.path.is_empty()) {
+            lock.containers.insert(c.path.clone(), c.name.clone());
+        }
+        for c in &output.0 {
+            for m in &c.members {
+                lock.members.insert(c.member_path(m), m.name.clone());
+            }
+        }
+        lock
+    }
+}
+
 /// Output container found by analyzer
 #[derive(Default, Debug)]
 pub struct Container {
     /// The short name of the struct (kind + capitalized suffix)
     pub name: String,
+    /// The JSON path of the property the container was generated for, such as `spec.endpoints`
+    pub path: String,
     /// The nestedness level the container was found in
     pub level: u8,
     /// Members or enum members of the container
     pub members: Vec<Member>,
     /// Documentation properties extracted for the container
     pub docs: Option<String>,
+    /// Title of the schema of the container
+    pub title: Option<String>,
     /// Whether this container is an enum
     pub is_enum: bool,
+    /// CEL rules from `x-kubernetes-validations` on the container itself
+    pub validations: Vec<ValidationRule>,
 }
 
 /// Output member belonging to an Container
@@ -42,9 +78,72 @@ pub struct Member {
     pub extra_annot: Vec<String>,
     /// Documentation properties extracted from the property
     pub docs: Option<String>,
+    /// Validation constraints set on the property
+    pub constraints: Constraints,
+    /// Kubernetes schema extensions (`x-kubernetes-*`) set on the property
+    ///
+    /// These are lost when deriving JsonSchema on the member type,
+    /// and must be re-inserted through a `schemars(schema_with)` helper.
+    pub extensions: BTreeMap<String, serde_json::Value>,
+    /// The schema value of an enum variant
+    pub value: Option<serde_json::Value>,
+}
+
+/// Validation constraints for a member
+///
+/// These are the plain schema constraints that are not expressed in the rust type.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Constraints {
+    pub pattern: Option<String>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub exclusive_minimum: bool,
+    pub exclusive_maximum: bool,
+    pub min_length: Option<i64>,
+    pub max_length: Option<i64>,
+    pub min_items: Option<i64>,
+    pub max_items: Option<i64>,
+    pub unique_items: bool,
+    pub min_properties: Option<i64>,
+    pub max_properties: Option<i64>,
+    /// Keys that must be present for members represented as maps
+    pub required: Vec<String>,
+    /// Constraints of the innermost items or values of arrays and maps, which are not checked
+    pub elements: Option<Box<Constraints>>,
+}
+
+impl Constraints {
+    /// Whether there are no constraints on the member itself
+    pub fn is_empty(&self) -> bool {
+        let own = Self {
+            elements: None,
+            ..self.clone()
+        };
+        own == Self::default()
+    }
 }
 
 impl Container {
+    /// Whether this is an enum with integer, number, boolean or mixed values
+    ///
+    /// These cannot derive their serde impls, which only support string variants.
+    pub fn is_non_string_enum(&self) -> bool {
+        self.is_enum
+            && self
+                .members
+                .iter()
+                .any(|m| m.value.as_ref().is_some_and(|v| !v.is_string()))
+    }
+
+    /// Whether this is an enum with a `#[default]` variant from the schema default
+    pub fn has_default_variant(&self) -> bool {
+        self.is_enum
+            && self
+                .members
+                .iter()
+                .any(|m| m.extra_annot.iter().any(|a| a == "#[default]"))
+    }
+
     pub fn uses_btreemaps(&self) -> bool {
         self.members.iter().any(|m| m.type_.contains("BTreeMap"))
     }
@@ -53,12 +152,30 @@ impl Container {
         self.members.iter().any(|m| m.type_.contains("HashMap"))
     }
 
-    pub fn uses_datetime(&self) -> bool {
-        self.members.iter().any(|m| m.type_.contains("DateTime"))
+    pub fn uses_datetime(&self, time: TimeType) -> bool {
+        let ident = time.date_time().split('<').next().unwrap_or_default();
+        self.members
+            .iter()
+            .any(|m| type_idents(&m.type_).any(|t| t == ident))
+    }
+
+    pub fn uses_date(&self, time: TimeType) -> bool {
+        self.members
+            .iter()
+            .any(|m| type_idents(&m.type_).any(|t| t == time.date()))
+    }
+
+    pub fn uses_quantity(&self) -> bool {
+        self.uses_type("Quantity")
+    }
+
+    pub fn uses_go_duration(&self) -> bool {
+        self.uses_type("GoDuration")
     }
 
-    pub fn uses_date(&self) -> bool {
-        self.members.iter().any(|m| m.type_.contains("NaiveDate"))
+    /// Whether any member type refers to the type with the given name
+    pub fn uses_type(&self, name: &str) -> bool {
+        self.members.iter().any(|m| m.uses_type(name))
     }
 
     pub fn uses_int_or_string(&self) -> bool {
@@ -80,6 +197,35 @@ impl Container {
     pub fn contains_conditions(&self) -> bool {
         self.members.iter().any(|m| m.type_.contains("Vec<Condition>"))
     }
+
+    pub fn has_validations(&self) -> bool {
+        !self.validations.is_empty() || self.members.iter().any(|m| !m.validations().is_empty())
+    }
+}
+
+impl Member {
+    /// Whether the member type refers to the type with the given name
+    pub fn uses_type(&self, name: &str) -> bool {
+        type_idents(&self.type_).any(|t| t == name)
+    }
+
+    /// The name of the member as it appears in the schema
+    ///
+    /// This is the name before `Container::rename`, recovered from the `rename` annotation if present.
+    pub fn original_name(&self) -> &str {
+        self.serde_annot
+            .iter()
+            .find_map(|a| a.strip_prefix("rename = \"").and_then(|a| a.strip_suffix('"')))
+            .unwrap_or(&self.name)
+    }
+
+    /// CEL rules from `x-kubernetes-validations` on the property
+    pub fn validations(&self) -> Vec<ValidationRule> {
+        self.extensions
+            .get("x-kubernetes-validations")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
 }
 
 impl Container {
@@ -131,6 +277,44 @@ impl Container {
         }
     }
 
+    /// The JSON path of a member, or an enum value, of the container
+    fn member_path(&self, m: &Member) -> String {
+        child_path(&self.path, m.original_name())
+    }
+
+    /// Use the member names recorded in a lock, suffixing unrecorded members that clash with them
+    fn lock_names(&mut self, lock: &Lock) {
+        let mut locked: Vec<Option<String>> = vec![];
+        for m in &self.members {
+            let name = lock.members.get(&self.member_path(m)).filter(|name| {
+                syn::parse_str::<syn::Ident>(name).is_ok() && !locked.iter().flatten().any(|l| &l == name)
+            });
+            locked.push(name.cloned());
+        }
+        let mut seen: Vec<String> = locked.iter().flatten().cloned().collect();
+        for (m, name) in self.members.iter_mut().zip(locked) {
+            let name = match name {
+                Some(name) => name,
+                None => {
+                    let mut name = m.name.clone();
+                    while seen.contains(&name) {
+                        name.push_str(if self.is_enum { "X" } else { "_x" });
+                    }
+                    seen.push(name.clone());
+                    name
+                }
+            };
+            if name != m.name {
+                let original = m.original_name().to_string();
+                m.serde_annot.retain(|a| !a.starts_with("rename = "));
+                if name != original {
+                    m.serde_annot.push(format!("rename = \"{}\"", original));
+                }
+                m.name = name;
+            }
+        }
+    }
+
     /// Add builder annotations
     pub fn builder_fields(&mut self) {
         for m in &mut self.members {
@@ -189,6 +373,144 @@ impl Output {
         }
         self
     }
+
+    /// Replace string members that have a pattern with newtypes validating the pattern
+    ///
+    /// One newtype is used per distinct pattern, named after the first member using it,
+    /// and suffixed if the name is taken by a container or another newtype.
+    /// Returns the names and patterns of the newtypes in order of appearance.
+    pub fn pattern_types(&mut self) -> Vec<(String, String)> {
+        let mut types: Vec<(String, String)> = vec![];
+        let mut taken: Vec<String> = self.0.iter().map(|c| c.name.clone()).collect();
+        for c in self.0.iter_mut().filter(|c| !c.is_enum) {
+            for m in &mut c.members {
+                let Some(pattern) = &m.constraints.pattern else {
+                    continue;
+                };
+                let optional = m.type_.starts_with("Option<");
+                if m.type_ != "String" && m.type_ != "Option<String>" {
+                    continue;
+                }
+                let name = match types.iter().find(|(_, p)| p == pattern) {
+                    Some((name, _)) => name.clone(),
+                    None => {
+                        let mut name = format!("{}{}", c.name, m.original_name().to_pascal_case());
+                        if taken.contains(&name) {
+                            name = (2..)
+                                .map(|i| format!("{name}{i}"))
+                                .find(|n| !taken.contains(n))
+                                .unwrap();
+                        }
+                        taken.push(name.clone());
+                        types.push((name.clone(), pattern.clone()));
+                        name
+                    }
+                };
+                m.type_ = if optional { format!("Option<{name}>") } else { name };
+            }
+        }
+        types
+    }
+
+    /// Use the container and member names recorded in a lock for the same JSON paths
+    ///
+    /// Other containers and members keep their names, but are suffixed if their name is recorded for another path.
+    /// This must be done after renaming the members.
+    pub fn lock_names(&mut self, lock: &Lock) {
+        let locked: Vec<Option<&String>> = self
+            .0
+            .iter()
+            .map(|c| lock.containers.get(&c.path).filter(|_| !c.path.is_empty()))
+            .collect();
+        let mut taken: Vec<String> = locked.iter().flatten().map(|n| n.to_string()).collect();
+        let mut renames = BTreeMap::new();
+        for (c, name) in self.0.iter().zip(&locked) {
+            let name = match name {
+                Some(name) => name.to_string(),
+                None if taken.contains(&c.name) => {
+                    let name = (2..)
+                        .map(|i| format!("{}{i}", c.name))
+                        .find(|n| !taken.contains(n))
+                        .unwrap();
+                    taken.push(name.clone());
+                    name
+                }
+                None => continue,
+            };
+            if name != c.name {
+                renames.insert(c.name.clone(), name);
+            }
+        }
+        self.rename_containers(&renames);
+        for c in &mut self.0 {
+            c.lock_names(lock);
+        }
+    }
+
+    /// Rename containers by their current names, in both container names and member types
+    pub fn rename_containers(&mut self, renames: &BTreeMap<String, String>) {
+        for c in &mut self.0 {
+            if let Some(name) = renames.get(&c.name) {
+                c.name = name.clone();
+            }
+            for m in &mut c.members {
+                m.type_ = replace_idents(&m.type_, |ident| renames.get(ident).map(String::as_str));
+            }
+        }
+    }
+
+    /// Replace a container with an external type in all member types
+    ///
+    /// Returns false if there is no container with the name.
+    pub fn use_type(&mut self, name: &str, type_: &str) -> bool {
+        let Some(i) = self.0.iter().position(|c| c.name == name) else {
+            return false;
+        };
+        self.0.remove(i);
+        for m in self.0.iter_mut().flat_map(|c| c.members.iter_mut()) {
+            m.type_ = replace_idents(&m.type_, |ident| (ident == name).then_some(type_));
+        }
+        true
+    }
+
+    /// Remove containers that cannot be reached from the outermost containers through member types
+    ///
+    /// The members of elided containers are not followed, so containers only they reference are removed.
+    /// Returns the names of the removed containers.
+    pub fn prune(&mut self, elided: &[String]) -> Vec<String> {
+        let top = self.0.iter().map(|c| c.level).min().unwrap_or_default();
+        let mut reachable: Vec<&str> = self
+            .0
+            .iter()
+            .filter(|c| c.level == top)
+            .map(|c| c.name.as_str())
+            .collect();
+        let mut i = 0;
+        while let Some(name) = reachable.get(i) {
+            if let Some(c) = self
+                .0
+                .iter()
+                .find(|c| c.name == *name && !elided.contains(&c.name))
+            {
+                for ident in c.members.iter().flat_map(|m| type_idents(&m.type_)) {
+                    if !reachable.contains(&ident) && self.0.iter().any(|c| c.name == ident) {
+                        reachable.push(ident);
+                    }
+                }
+            }
+            i += 1;
+        }
+        let reachable: Vec<String> = reachable.into_iter().map(String::from).collect();
+        let mut pruned = vec![];
+        self.0.retain(|c| {
+            let keep = reachable.contains(&c.name);
+            if !keep {
+                pruned.push(c.name.clone());
+            }
+            keep
+        });
+        pruned
+    }
 }
 
 /// Type used for additionalProperties maps
@@ -208,10 +530,101 @@ impl MapType {
     }
 }
 
+/// Strategy for naming nested containers
+#[derive(clap::ValueEnum, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Naming {
+    /// The kind followed by every property on the JSON path, such as PrometheusSpecStorageVolumeClaimTemplate
+    #[default]
+    Full,
+    /// The kind followed by the shortest suffix of the JSON path not shared with another container
+    Suffix,
+    /// The kind followed by the title of the schema, or the full name for schemas without one
+    Title,
+}
+
+/// Type used for date and date-time formatted strings
+#[derive(clap::ValueEnum, Clone, Copy, Default, Debug)]
+#[clap(rename_all = "PascalCase")]
+pub enum TimeType {
+    /// chrono::DateTime<Utc> and chrono::NaiveDate
+    #[default]
+    Chrono,
+    /// k8s_openapi's meta::v1::Time and its re-exported chrono::NaiveDate
+    Time,
+    /// jiff::Timestamp and jiff::civil::Date
+    Jiff,
+    /// Plain strings
+    String,
+}
+impl TimeType {
+    pub fn date_time(&self) -> &str {
+        match self {
+            Self::Chrono => "DateTime<Utc>",
+            Self::Time => "Time",
+            Self::Jiff => "Timestamp",
+            Self::String => "String",
+        }
+    }
+
+    pub fn date(&self) -> &str {
+        match self {
+            // chrono docs advocate for NaiveDate over Date<Utc>
+            Self::Chrono | Self::Time => "NaiveDate",
+            Self::Jiff => "Date",
+            Self::String => "String",
+        }
+    }
+
+    /// Prelude imports needed for the date-time type
+    pub fn date_time_import(&self) -> Option<&str> {
+        match self {
+            Self::Chrono => Some("chrono::{DateTime, Utc}"),
+            Self::Time => Some("k8s_openapi::apimachinery::pkg::apis::meta::v1::Time"),
+            Self::Jiff => Some("jiff::Timestamp"),
+            Self::String => None,
+        }
+    }
+
+    /// Prelude imports needed for the date type
+    pub fn date_import(&self) -> Option<&str> {
+        match self {
+            Self::Chrono => Some("chrono::naive::NaiveDate"),
+            Self::Time => Some("k8s_openapi::chrono::naive::NaiveDate"),
+            Self::Jiff => Some("jiff::civil::Date"),
+            Self::String => None,
+        }
+    }
+}
+
+/// Identifiers making up a stringified type such as `Option<BTreeMap<String, DateTime<Utc>>>`
+fn type_idents(type_: &str) -> impl Iterator<Item = &str> {
+    type_
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|t| !t.is_empty())
+}
+
+/// Replace identifiers in a stringified type, leaving longer identifiers containing them alone
+fn replace_idents<'a>(type_: &str, replacement: impl Fn(&str) -> Option<&'a str>) -> String {
+    let mut replaced = String::new();
+    let mut rest = type_;
+    while let Some(start) = rest.find(|c: char| c.is_alphanumeric() || c == '_') {
+        replaced.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        replaced.push_str(replacement(&rest[..end]).unwrap_or(&rest[..end]));
+        rest = &rest[end..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
 // unit tests
 #[cfg(test)]
 mod test {
-    use super::{Container, Member};
+    use super::{Constraints, Container, Lock, Member, Output};
+    use std::collections::BTreeMap;
     fn name_only_enum_member(name: &str) -> Member {
         Member {
             name: name.to_string(),
@@ -219,6 +632,9 @@ mod test {
             serde_annot: vec![],
             extra_annot: vec![],
             docs: None,
+            constraints: Default::default(),
+            extensions: Default::default(),
+            value: None,
         }
     }
     fn name_only_int_member(name: &str) -> Member {
@@ -228,6 +644,9 @@ mod test {
             serde_annot: vec![],
             extra_annot: vec![],
             docs: None,
+            constraints: Default::default(),
+            extensions: Default::default(),
+            value: None,
         }
     }
 
@@ -235,6 +654,7 @@ mod test {
     fn rename_avoids_producing_name_clashes() {
         let mut c = Container {
             name: "EndpointRelabelingsAction".to_string(),
+            path: "spec.endpoints.relabelings.action".to_string(),
             level: 1,
             members: vec![
                 name_only_enum_member("replace"),
@@ -248,7 +668,9 @@ mod test {
                 name_only_enum_member("JwksUri"),
             ],
             docs: None,
+            title: None,
             is_enum: true,
+            validations: vec![],
         };
 
         c.rename();
@@ -264,6 +686,7 @@ mod test {
         // ditto for a struct
         let mut cs = Container {
             name: "FakeStruct".to_string(),
+            path: "spec.fakeStruct".to_string(),
             level: 1,
             members: vec![
                 // deliberately contrarian examples
@@ -273,7 +696,9 @@ mod test {
                 name_only_int_member("JwksUri"),
             ],
             docs: None,
+            title: None,
             is_enum: false,
+            validations: vec![],
         };
         cs.rename();
         assert_eq!(&cs.members[0].name, "jwks_uri");
@@ -281,4 +706,200 @@ mod test {
         assert_eq!(&cs.members[2].name, "jwks_uri_x_x");
         assert_eq!(&cs.members[3].name, "jwks_uri_x_x_x");
     }
+
+    #[test]
+    fn pattern_types() {
+        let pattern = |type_: &str, m: Member, p: &str| Member {
+            type_: type_.to_string(),
+            constraints: Constraints {
+                pattern: Some(p.to_string()),
+                ..Constraints::default()
+            },
+            ..m
+        };
+        let mut output = Output(vec![
+            Container {
+                name: "FooSpec".to_string(),
+                members: vec![
+                    pattern("String", name_only_int_member("name"), "^[a-z]+$"),
+                    pattern("Option<String>", name_only_int_member("host"), "^[a-z.]+$"),
+                    pattern("Option<Vec<String>>", name_only_int_member("hosts"), "^[a-z.]+$"),
+                ],
+                ..Container::default()
+            },
+            Container {
+                name: "FooSpecBackend".to_string(),
+                members: vec![pattern(
+                    "Option<String>",
+                    name_only_int_member("addr"),
+                    "^[a-z.]+$",
+                )],
+                ..Container::default()
+            },
+        ]);
+        let types = output.pattern_types();
+        assert_eq!(
+            types,
+            vec![
+                ("FooSpecName".to_string(), "^[a-z]+$".to_string()),
+                ("FooSpecHost".to_string(), "^[a-z.]+$".to_string()),
+            ]
+        );
+        assert_eq!(output.0[0].members[0].type_, "FooSpecName");
+        assert_eq!(output.0[0].members[1].type_, "Option<FooSpecHost>");
+        assert_eq!(output.0[0].members[2].type_, "Option<Vec<String>>");
+        assert_eq!(output.0[1].members[0].type_, "Option<FooSpecHost>");
+    }
+
+    #[test]
+    fn pattern_types_avoid_taken_names() {
+        let pattern = |m: Member, p: &str| Member {
+            type_: "String".to_string(),
+            constraints: Constraints {
+                pattern: Some(p.to_string()),
+                ..Constraints::default()
+            },
+            ..m
+        };
+        let mut output = Output(vec![
+            Container {
+                name: "FooSpec".to_string(),
+                members: vec![
+                    pattern(name_only_int_member("name"), "^[a-z]+$"),
+                    pattern(name_only_int_member("backend_name"), "^[a-z.]+$"),
+                ],
+                ..Container::default()
+            },
+            Container {
+                name: "FooSpecBackend".to_string(),
+                members: vec![pattern(name_only_int_member("name"), "^[a-z-]+$")],
+                ..Container::default()
+            },
+            Container {
+                name: "FooSpecName".to_string(),
+                ..Container::default()
+            },
+        ]);
+        let names: Vec<_> = output.pattern_types().into_iter().map(|(name, _)| name).collect();
+        // clashing with a container, and with the newtype of another member
+        assert_eq!(
+            names,
+            vec!["FooSpecName2", "FooSpecBackendName", "FooSpecBackendName2"]
+        );
+        assert_eq!(output.0[1].members[0].type_, "FooSpecBackendName2");
+    }
+
+    #[test]
+    fn use_type_and_prune() {
+        let typed_member = |name: &str, type_: &str| Member {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            ..Member::default()
+        };
+        let container = |name: &str, level: u8, members: Vec<Member>| Container {
+            name: name.to_string(),
+            level,
+            members,
+            ..Container::default()
+        };
+        let mut output = Output(vec![
+            container("Foo", 0, vec![typed_member("spec", "FooSpec")]),
+            container(
+                "FooSpec",
+                1,
+                vec![
+                    typed_member("template", "Option<FooSpecTemplate>"),
+                    typed_member("templates", "Vec<FooSpecTemplateSpec>"),
+                ],
+            ),
+            container(
+                "FooSpecTemplate",
+                2,
+                vec![typed_member("spec", "FooSpecTemplateSpec")],
+            ),
+            container("FooSpecTemplateSpec", 3, vec![typed_member("image", "String")]),
+            container("FooSpecTemplateMeta", 3, vec![]),
+        ]);
+        assert!(!output.use_type("FooSpecMissing", "Bar"));
+        assert!(output.use_type("FooSpecTemplate", "k8s_openapi::api::core::v1::PodTemplateSpec"));
+        assert_eq!(
+            output.0[1].members[0].type_,
+            "Option<k8s_openapi::api::core::v1::PodTemplateSpec>"
+        );
+        // longer names containing the replaced name are left alone
+        assert_eq!(output.0[1].members[1].type_, "Vec<FooSpecTemplateSpec>");
+
+        assert_eq!(output.prune(&[]), vec!["FooSpecTemplateMeta".to_string()]);
+        let names: Vec<_> = output.0.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Foo", "FooSpec", "FooSpecTemplateSpec"]);
+
+        // elided containers are kept, but not what only they reference
+        assert_eq!(
+            output.prune(&["FooSpec".to_string()]),
+            vec!["FooSpecTemplateSpec".to_string()]
+        );
+        let names: Vec<_> = output.0.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Foo", "FooSpec"]);
+    }
+
+    #[test]
+    fn lock_names() {
+        let typed_member = |name: &str, type_: &str| Member {
+            name: name.to_string(),
+            type_: type_.to_string(),
+            ..Member::default()
+        };
+        let container = |name: &str, path: &str, members: Vec<Member>| Container {
+            name: name.to_string(),
+            path: path.to_string(),
+            level: path.split('.').count() as u8,
+            members,
+            ..Container::default()
+        };
+        let output = Output(vec![
+            container(
+                "FooSpec",
+                "spec",
+                vec![
+                    typed_member("endpoints", "Vec<FooSpecEndpoints>"),
+                    typed_member("port", "i32"),
+                    typed_member("targetPort", "Option<i32>"),
+                ],
+            ),
+            container("FooSpecEndpoints", "spec.endpoints", vec![]),
+            container("FooSpecEndpointsTls", "spec.endpoints.tls", vec![]),
+        ]);
+        let mut output = output.rename();
+        let lock = Lock {
+            containers: BTreeMap::from([
+                ("spec".to_string(), "FooSpec".to_string()),
+                ("spec.endpoints".to_string(), "FooSpecEndpointsTls".to_string()),
+            ]),
+            members: BTreeMap::from([
+                ("spec.port".to_string(), "target_port".to_string()),
+                ("spec.endpoints".to_string(), "endpoints".to_string()),
+            ]),
+        };
+        output.lock_names(&lock);
+
+        // locked names are used, and new paths clashing with them are suffixed
+        let names: Vec<_> = output.0.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["FooSpec", "FooSpecEndpointsTls", "FooSpecEndpointsTls2"]
+        );
+        let spec = &output.0[0];
+        assert_eq!(spec.members[0].type_, "Vec<FooSpecEndpointsTls>");
+        assert_eq!(spec.members[1].name, "target_port");
+        assert_eq!(spec.members[1].serde_annot, vec!["rename = \"port\""]);
+        assert_eq!(spec.members[2].name, "target_port_x");
+        assert_eq!(spec.members[2].serde_annot, vec!["rename = \"targetPort\""]);
+
+        // the lock of the output keeps the names
+        let relocked = Lock::from_output(&output);
+        assert_eq!(relocked.containers["spec.endpoints.tls"], "FooSpecEndpointsTls2");
+        assert_eq!(relocked.members["spec.targetPort"], "target_port_x");
+        output.lock_names(&relocked);
+        assert_eq!(Lock::from_output(&output), relocked);
+    }
 }
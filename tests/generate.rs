@@ -0,0 +1,647 @@
+//! Checks of the code kopium generates for the CRDs in this directory
+//!
+//! Generated code is kept in tests/generated and compiled as part of this test, so the snapshots
+//! are checked against the current output of kopium. Run with KOPIUM_BLESS=1 to update them.
+// the generated code is meant to be public, and is only partially used here
+#![allow(dead_code)]
+use std::process::Command;
+
+/// Run kopium and return what it printed, and logged at the warn level
+fn kopium(args: &[&str]) -> (String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_kopium"))
+        .args(args)
+        .env("RUST_LOG", "warn")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        output.status.success(),
+        "kopium {} failed: {stderr}",
+        args.join(" ")
+    );
+    (String::from_utf8(output.stdout).unwrap(), stderr)
+}
+
+/// Check that the snapshot of generated code in tests/generated is up to date
+fn snapshot(name: &str, args: &[&str]) {
+    let (generated, _) = kopium(args);
+    let path = format!("{}/tests/generated/{name}.rs", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var_os("KOPIUM_BLESS").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let snapshot = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        snapshot == generated,
+        "{path} is outdated, run with KOPIUM_BLESS=1 to update it"
+    );
+}
+
+mod cel_validation {
+    include!("generated/cel_validation.rs");
+}
+
+#[test]
+fn cel_validation() {
+    snapshot(
+        "cel_validation",
+        &["-f", "tests/validation-crd.yaml", "--cel-validation"],
+    );
+
+    use cel_validation::*;
+    let spec: GatewaySpec = serde_json::from_value(serde_json::json!({
+        "labels": {"app": "gateway"},
+        "listeners": [{"name": "http", "port": 80}, {"name": "https", "port": 443}],
+        "selector": {"app": "gateway"},
+        "tls": {"mode": "Terminate"},
+    }))
+    .unwrap();
+    assert!(spec.validate_rules().is_ok());
+
+    let spec: GatewaySpec = serde_json::from_value(serde_json::json!({
+        "labels": {"app": "gateway"},
+        "listeners": [{"name": "http", "port": 80}, {"name": "https", "port": 0}],
+        "selector": {"tier": "edge"},
+        "tls": {"mode": ""},
+    }))
+    .unwrap();
+    let errors = spec.validate_rules().unwrap_err();
+    let fields: Vec<_> = errors
+        .iter()
+        .map(|e| (e.field.as_str(), e.rule.as_str()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            (".listeners[1]", "self.port > 0"),
+            (".selector", "has(self.app)"),
+            (".tls", "self.mode != ''"),
+        ]
+    );
+    assert_eq!(errors[0].message, "port must be positive");
+    assert_eq!(errors[2].message, "failed rule: self.mode != ''");
+}
+
+mod generated_constraints {
+    include!("generated/generated_constraints.rs");
+}
+
+#[test]
+fn generated_constraints() {
+    snapshot(
+        "generated_constraints",
+        &["-f", "tests/validation-crd.yaml", "--constraints", "generated"],
+    );
+
+    use generated_constraints::*;
+    let spec: GatewaySpec = serde_json::from_value(serde_json::json!({
+        "hostnames": ["example.com", "example.com"],
+        "labels": {"tier": "edge"},
+        "listeners": [{"name": "http", "port": 80}, {"name": "HTTPS", "port": 0}],
+        "selector": {"app": ""},
+    }))
+    .unwrap();
+    let errors = spec.validate_rules().unwrap_err();
+    let fields: Vec<_> = errors
+        .iter()
+        .map(|e| (e.field.as_str(), e.rule.as_str()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            (".hostnames", "uniqueItems: true"),
+            (".labels", "required: app"),
+            (".listeners[1].name", "pattern: ^[a-z]+$"),
+            (".listeners[1].port", "minimum: 1"),
+            (".selector.app", "minLength: 1"),
+        ]
+    );
+    // only the members themselves are checked, not their items
+    let (_, warnings) = kopium(&["-f", "tests/validation-crd.yaml", "--constraints", "generated"]);
+    assert!(warnings.contains("constraints on the items or values of GatewaySpec.hostnames are not checked"));
+}
+
+mod validator_constraints {
+    include!("generated/validator_constraints.rs");
+}
+
+#[test]
+fn validator_constraints() {
+    snapshot(
+        "validator_constraints",
+        &["-f", "tests/validation-crd.yaml", "--constraints", "validator"],
+    );
+
+    use validator::Validate;
+    use validator_constraints::*;
+    let spec: GatewaySpec = serde_json::from_value(serde_json::json!({
+        "labels": {"app": "gateway"},
+        "listeners": [{"name": "http", "port": 80}],
+        "selector": {"app": "gateway"},
+    }))
+    .unwrap();
+    assert!(spec.validate().is_ok());
+
+    let spec: GatewaySpec = serde_json::from_value(serde_json::json!({
+        "labels": {"app": "gateway"},
+        "listeners": [{"name": "HTTP", "port": 80}],
+        "selector": {"app": ""},
+    }))
+    .unwrap();
+    let errors = spec.validate().unwrap_err();
+    let mut fields: Vec<_> = errors.errors().keys().copied().collect();
+    fields.sort();
+    assert_eq!(fields, vec!["listeners", "selector"]);
+}
+
+#[test]
+fn garde_constraints() {
+    // garde is not a dev-dependency, so only the attributes are checked
+    let (generated, _) = kopium(&["-f", "tests/validation-crd.yaml", "--constraints", "garde"]);
+    for attrs in [
+        "#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, garde::Validate)]",
+        "    #[garde(length(max = 8), dive)]\n    pub listeners: Vec<GatewayListeners>,",
+        "    #[garde(pattern(r\"^[a-z]+$\"), length(chars, min = 1, max = 15))]\n    pub name: String,",
+        "    #[garde(range(min = 1, max = 65535))]\n    pub port: i32,",
+        "    #[garde(skip)]\n    pub mode: Option<String>,",
+    ] {
+        assert!(generated.contains(attrs), "missing {attrs} in\n{generated}");
+    }
+}
+
+mod schema_validations {
+    include!("generated/schema_validations.rs");
+}
+
+#[test]
+fn schema_validations() {
+    snapshot("schema_validations", &["-f", "tests/validation-crd.yaml", "-A"]);
+
+    use kube::CustomResourceExt;
+    use schema_validations::*;
+    let crd = serde_json::to_value(Gateway::crd()).unwrap();
+    let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+    let rules = |schema: &serde_json::Value| schema["x-kubernetes-validations"][0]["rule"].clone();
+    // rules on the structs themselves, as well as on their members
+    assert_eq!(rules(spec), "size(self.listeners) <= 8");
+    assert_eq!(rules(&spec["properties"]["listeners"]["items"]), "self.port > 0");
+    assert_eq!(rules(&spec["properties"]["selector"]), "has(self.app)");
+    assert_eq!(rules(&spec["properties"]["tls"]), "self.mode != ''");
+}
+
+mod external_derives {
+    include!("generated/external_derives.rs");
+}
+
+#[test]
+fn external_derives() {
+    let args = [
+        "-f",
+        "tests/validation-crd.yaml",
+        "-D",
+        "PartialEq",
+        "-D",
+        "Eq",
+        "-D",
+        "Hash",
+        "--use",
+        "spec.selector=k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector",
+    ];
+    snapshot("external_derives", &args);
+
+    // types from --use are not known to implement anything, unlike the generated ones
+    let (generated, warnings) = kopium(&args);
+    assert!(generated.contains("#[derive(CustomResource, Serialize, Deserialize, Clone, Debug)]\n"));
+    assert!(generated.contains(
+        "#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]\npub struct GatewayListeners {"
+    ));
+    assert!(warnings.contains(
+        "not deriving PartialEq for GatewaySpec as member selector of type \
+         k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector is not known to implement it"
+    ));
+}
+
+mod quantity_derives {
+    include!("generated/quantity_derives.rs");
+}
+
+#[test]
+fn quantity_derives() {
+    snapshot(
+        "quantity_derives",
+        &[
+            "-f",
+            "tests/validation-crd.yaml",
+            "--quantity",
+            "-D",
+            "PartialEq",
+            "-D",
+            "Eq",
+            "-D",
+            "Hash",
+        ],
+    );
+
+    // Quantity only implements PartialEq, so neither do the structs containing it
+    use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+    use quantity_derives::*;
+    use std::collections::{BTreeMap, HashSet};
+    let resources = GatewayResources {
+        limits: Some(BTreeMap::from([(
+            "cpu".to_string(),
+            Quantity("500m".to_string()),
+        )])),
+    };
+    assert_eq!(resources.clone(), resources);
+    let listeners = HashSet::from([GatewayListeners {
+        name: "http".to_string(),
+        port: 80,
+    }]);
+    assert_eq!(listeners.len(), 1);
+}
+
+mod enum_fallback_unknown {
+    include!("generated/enum_fallback_unknown.rs");
+}
+
+#[test]
+fn enum_fallback_unknown() {
+    snapshot(
+        "enum_fallback_unknown",
+        &[
+            "-f",
+            "tests/enum-crd.yaml",
+            "-A",
+            "-D",
+            "PartialEq",
+            "--enum-fallback",
+            "unknown",
+        ],
+    );
+
+    use enum_fallback_unknown::*;
+    let spec: WidgetSpec = serde_json::from_value(serde_json::json!({
+        "mode": "read-only",
+        "priority": 4,
+        "protocol": "SCTP",
+    }))
+    .unwrap();
+    assert_eq!(spec.mode, Some(WidgetMode::ReadOnly));
+    assert_eq!(spec.priority, Some(WidgetPriority::Unknown));
+    assert_eq!(spec.protocol, WidgetProtocol::Unknown);
+    assert_eq!(
+        serde_json::to_value(&spec).unwrap(),
+        serde_json::json!({"mode": "read-only", "priority": "Unknown", "protocol": "Unknown"})
+    );
+}
+
+mod enum_fallback_other {
+    include!("generated/enum_fallback_other.rs");
+}
+
+#[test]
+fn enum_fallback_other() {
+    snapshot(
+        "enum_fallback_other",
+        &[
+            "-f",
+            "tests/enum-crd.yaml",
+            "-A",
+            "-D",
+            "PartialEq",
+            "--enum-fallback",
+            "other",
+        ],
+    );
+
+    use enum_fallback_other::*;
+    let value = serde_json::json!({"mode": "read-only", "priority": 4, "protocol": "SCTP"});
+    let spec: WidgetSpec = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(spec.mode, Some(WidgetMode::ReadOnly));
+    assert_eq!(spec.priority, Some(WidgetPriority::Other(serde_json::json!(4))));
+    assert_eq!(spec.protocol, WidgetProtocol::Other("SCTP".to_string()));
+    assert_eq!(serde_json::to_value(&spec).unwrap(), value);
+    // unknown values of string enums still have to be strings
+    let spec = serde_json::from_value::<WidgetSpec>(serde_json::json!({"protocol": 1}));
+    assert!(spec.is_err());
+
+    // the schema only lists the values of the schema
+    use kube::CustomResourceExt;
+    let crd = serde_json::to_value(Widget::crd()).unwrap();
+    let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+    assert_eq!(
+        spec["properties"]["protocol"]["enum"],
+        serde_json::json!(["TCP", "UDP", "http/2"])
+    );
+    assert_eq!(
+        spec["properties"]["priority"]["enum"],
+        serde_json::json!([1, 2, 3])
+    );
+}
+
+mod enum_defaults {
+    include!("generated/enum_defaults.rs");
+}
+
+#[test]
+fn enum_defaults() {
+    let args = ["-f", "tests/enum-crd.yaml", "-D", "PartialEq", "-D", "Default"];
+    snapshot("enum_defaults", &args);
+
+    // the default of the schema is the default variant
+    use enum_defaults::*;
+    assert_eq!(WidgetMode::default(), WidgetMode::ReadOnly);
+
+    // enums without a default in the schema do not derive Default, nor do the structs using them
+    let (generated, warnings) = kopium(&args);
+    assert!(generated
+        .contains("#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]\npub enum WidgetProtocol {"));
+    assert!(
+        generated.contains("#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq)]\n")
+    );
+    assert!(warnings.contains("not deriving Default for WidgetSpec as member protocol"));
+
+    // the default variant is only marked when deriving Default
+    let (generated, _) = kopium(&["-f", "tests/enum-crd.yaml"]);
+    assert!(!generated.contains("#[default]"));
+}
+
+mod enum_impls {
+    include!("generated/enum_impls.rs");
+}
+
+#[test]
+fn enum_impls() {
+    snapshot(
+        "enum_impls",
+        &["-f", "tests/enum-crd.yaml", "-D", "PartialEq", "--enum-impls"],
+    );
+
+    // the values of the schema are used rather than the variant names
+    use enum_impls::*;
+    for protocol in WidgetProtocol::ALL {
+        let value = serde_json::to_value(protocol).unwrap();
+        assert_eq!(value, protocol.to_string());
+        assert_eq!(&protocol.to_string().parse::<WidgetProtocol>().unwrap(), protocol);
+    }
+    assert_eq!(WidgetProtocol::Http2.to_string(), "http/2");
+    assert_eq!(WidgetMode::ReadWrite.as_ref(), "read-write");
+    assert_eq!(
+        "Http2".parse::<WidgetProtocol>().unwrap_err(),
+        r#"unknown variant "Http2", expected one of TCP, UDP, http/2"#
+    );
+}
+
+mod targeted {
+    include!("generated/targeted.rs");
+}
+
+#[test]
+fn targeted() {
+    let args = [
+        "-f",
+        "tests/validation-crd.yaml",
+        "--import",
+        "schemars::JsonSchema",
+        "-D",
+        "GatewayTls=Default",
+        "-D",
+        "GatewayTls=JsonSchema",
+        "--attr",
+        "GatewayTls=#[serde(deny_unknown_fields)]",
+        "--attr",
+        r#"GatewayListeners.port=#[serde(alias = "portNumber")]"#,
+        "-D",
+        "Missing=PartialEq",
+    ];
+    snapshot("targeted", &args);
+
+    use targeted::*;
+    let tls: GatewayTls = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(tls.mode, GatewayTls::default().mode);
+    assert!(serde_json::from_value::<GatewayTls>(serde_json::json!({"other": ""})).is_err());
+    let schema = serde_json::to_value(schemars::schema_for!(GatewayTls)).unwrap();
+    assert_eq!(schema["title"], "GatewayTls");
+
+    let listeners: GatewayListeners =
+        serde_json::from_value(serde_json::json!({"name": "http", "portNumber": 80})).unwrap();
+    assert_eq!(listeners.port, 80);
+
+    let (_, warnings) = kopium(&args);
+    assert!(warnings.contains("--derive Missing=PartialEq does not match any generated container"));
+}
+
+/// The names of the structs and enums in generated code
+fn containers(generated: &str) -> Vec<&str> {
+    generated
+        .lines()
+        .filter_map(|l| {
+            l.strip_prefix("pub struct ")
+                .or_else(|| l.strip_prefix("pub enum "))
+        })
+        .map(|l| l.trim_end_matches(" {"))
+        .collect()
+}
+
+#[test]
+fn elide() {
+    let elided = |args: &[&str]| {
+        let (all, _) = kopium(&["-f", "tests/servicemon-crd.yaml"]);
+        let (generated, warnings) = kopium(&[&["-f", "tests/servicemon-crd.yaml"], args].concat());
+        let kept = containers(&generated);
+        let elided: Vec<_> = containers(&all)
+            .into_iter()
+            .filter(|c| !kept.contains(c))
+            .map(String::from)
+            .collect();
+        (elided, warnings)
+    };
+
+    let (names, _) = elided(&["-e", "ServiceMonitorEndpoints*Secret"]);
+    assert_eq!(
+        names,
+        vec![
+            "ServiceMonitorEndpointsBearerTokenSecret",
+            "ServiceMonitorEndpointsOauth2ClientIdSecret",
+            "ServiceMonitorEndpointsOauth2ClientSecret",
+            "ServiceMonitorEndpointsTlsConfigCaSecret",
+            "ServiceMonitorEndpointsTlsConfigCertSecret",
+            "ServiceMonitorEndpointsTlsConfigKeySecret",
+        ]
+    );
+
+    // regexes are anchored like globs
+    let (names, _) = elided(&["-e", "re:ServiceMonitorEndpoints(Metric)?Relabelings"]);
+    assert_eq!(
+        names,
+        vec![
+            "ServiceMonitorEndpointsMetricRelabelings",
+            "ServiceMonitorEndpointsRelabelings",
+        ]
+    );
+
+    // the containers below elided ones are kept unless pruned
+    let (names, _) = elided(&["-e", "ServiceMonitorEndpointsOauth2"]);
+    assert_eq!(names, vec!["ServiceMonitorEndpointsOauth2"]);
+    let (names, _) = elided(&["-e", "ServiceMonitorEndpointsOauth2", "--prune-elided"]);
+    assert_eq!(
+        names,
+        vec![
+            "ServiceMonitorEndpointsOauth2",
+            "ServiceMonitorEndpointsOauth2ClientId",
+            "ServiceMonitorEndpointsOauth2ClientIdConfigMap",
+            "ServiceMonitorEndpointsOauth2ClientIdSecret",
+            "ServiceMonitorEndpointsOauth2ClientSecret",
+        ]
+    );
+
+    let (names, warnings) = elided(&["-e", "ServiceMonitorEndpoints*", "-e", "Monitor*"]);
+    assert!(names.contains(&"ServiceMonitorEndpoints".to_string()));
+    assert!(warnings.contains("--elide Monitor* does not match any generated container"));
+    assert!(!warnings.contains("--elide ServiceMonitorEndpoints*"));
+}
+
+mod typed_formats {
+    include!("generated/typed_formats.rs");
+}
+
+#[test]
+fn typed_formats() {
+    let args = [
+        "-f",
+        "tests/format-crd.yaml",
+        "-A",
+        "--typed-durations",
+        "--typed-formats",
+        "--secrets",
+        "--byte-strings",
+        "-D",
+        "PartialEq",
+        "-D",
+        "Default",
+    ];
+    snapshot("typed_formats", &args);
+
+    // empty emails and hostnames are invalid, so structs requiring them have no Default
+    let (generated, warnings) = kopium(&args);
+    assert!(generated.contains(
+        "#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]\npub struct Email(String);"
+    ));
+    assert!(generated.contains(
+        "#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]\npub struct Hostname(String);"
+    ));
+    assert!(warnings.contains("not deriving Default for MailerSpec as member from of type Email"));
+
+    use kube::CustomResourceExt;
+    use typed_formats::*;
+    let crd = serde_json::to_value(Mailer::crd()).unwrap();
+    let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+    assert_eq!(spec["properties"]["from"]["format"], "email");
+    assert_eq!(spec["properties"]["password"]["format"], "password");
+    assert_eq!(spec["properties"]["relay"]["format"], "hostname");
+    assert_eq!(spec["properties"]["timeout"]["type"], "string");
+    assert_eq!(spec["properties"]["certificate"]["type"], "string");
+    assert_eq!(spec["properties"]["certificate"]["format"], "byte");
+
+    let spec =
+        serde_json::json!({"from": "kopium@clux.dev", "relay": "smtp.clux.dev", "certificate": "a29waXVt"});
+    let spec: MailerSpec = serde_json::from_value(spec).unwrap();
+    assert_eq!(spec.certificate.unwrap().0, b"kopium");
+}
+
+mod jiff_times {
+    include!("generated/jiff_times.rs");
+}
+
+#[test]
+fn jiff_times() {
+    let args = [
+        "-f",
+        "tests/format-crd.yaml",
+        "-A",
+        "--time-type",
+        "Jiff",
+        "-D",
+        "PartialEq",
+        "-D",
+        "Default",
+    ];
+    snapshot("jiff_times", &args);
+
+    // schemars has no jiff support, so generated types describe the formatted strings
+    use jiff_times::*;
+    use kube::CustomResourceExt;
+    let crd = serde_json::to_value(Mailer::crd()).unwrap();
+    let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+    assert_eq!(spec["properties"]["sendAfter"]["type"], "string");
+    assert_eq!(spec["properties"]["sendAfter"]["format"], "date-time");
+    assert_eq!(spec["properties"]["holidays"]["items"]["type"], "string");
+    assert_eq!(spec["properties"]["holidays"]["items"]["format"], "date");
+
+    let spec = serde_json::json!({
+        "from": "kopium@clux.dev",
+        "relay": "smtp.clux.dev",
+        "sendAfter": "2024-05-01T10:00:00Z",
+        "holidays": ["2024-12-25"],
+    });
+    let spec: MailerSpec = serde_json::from_value(spec).unwrap();
+    assert_eq!(spec.send_after, Some("2024-05-01T10:00:00Z".parse().unwrap()));
+    assert_eq!(spec.holidays, Some(vec![jiff::civil::date(2024, 12, 25)]));
+}
+
+#[test]
+fn config() {
+    let (generated, _) = kopium(&["--config", "tests/config.yaml"]);
+    assert!(generated.contains("pub struct WidgetSpec {"));
+    assert!(generated.contains(
+        "#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]\npub enum WidgetMode {"
+    ));
+    assert!(generated.contains("impl std::str::FromStr for WidgetMode {"));
+
+    // options from the command line replace those from the config file, even lists and flags
+    let (generated, _) = kopium(&[
+        "--config",
+        "tests/config.yaml",
+        "-f",
+        "tests/validation-crd.yaml",
+        "-D",
+        "Hash",
+        "--enum-impls=false",
+    ]);
+    assert!(generated.contains("pub struct GatewaySpec {"));
+    assert!(generated
+        .contains("#[derive(Serialize, Deserialize, Clone, Debug, Hash)]\npub struct GatewayListeners {"));
+    let (generated, _) = kopium(&["--config", "tests/config.yaml", "--enum-impls=false"]);
+    assert!(!generated.contains("impl std::str::FromStr"));
+}
+
+mod pattern_types {
+    include!("generated/pattern_types.rs");
+}
+
+#[test]
+fn pattern_types() {
+    let args = [
+        "-f",
+        "tests/validation-crd.yaml",
+        "--pattern-types",
+        "-D",
+        "PartialEq",
+        "-D",
+        "Default",
+    ];
+    snapshot("pattern_types", &args);
+
+    use pattern_types::*;
+    let listeners = serde_json::json!({"name": "http", "port": 80});
+    let listeners: GatewayListeners = serde_json::from_value(listeners).unwrap();
+    assert_eq!(&*listeners.name, "http");
+    let listeners = serde_json::json!({"name": "HTTP", "port": 80});
+    assert!(serde_json::from_value::<GatewayListeners>(listeners).is_err());
+
+    // an empty string does not match the pattern, so there is no Default to build one
+    let (generated, warnings) = kopium(&args);
+    assert!(generated.contains(
+        "#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]\npub struct GatewayListeners {"
+    ));
+    assert!(warnings.contains("not deriving Default for GatewayListeners as member name"));
+}
@@ -0,0 +1,185 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/validation-crd.yaml --cel-validation
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+    pub use std::collections::BTreeMap;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+}
+use self::prelude::*;
+
+/// A validation rule from the schema that did not hold
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    /// Path to the field the rule applies to
+    pub field: String,
+    /// The CEL rule or schema constraint that failed
+    pub rule: String,
+    /// The message of the rule, or the reason it could not be evaluated
+    pub message: String,
+}
+
+fn validate_cel<T: Serialize>(program: &Result<cel_interpreter::Program, String>, value: &T, field: &str, rule: &str, message: &str, errors: &mut Vec<ValidationError>) {
+    let result = program.as_ref().map_err(|e| e.clone()).and_then(|program| {
+        let mut context = cel_interpreter::Context::default();
+        context.add_variable("self", value).map_err(|e| e.to_string())?;
+        program.execute(&context).map_err(|e| e.to_string())
+    });
+    let message = match result {
+        Ok(cel_interpreter::Value::Bool(true)) => return,
+        Ok(_) => message.to_string(),
+        Err(e) => format!("{message}: {e}"),
+    };
+    errors.push(ValidationError {
+        field: field.to_string(),
+        rule: rule.to_string(),
+        message,
+    });
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug)]
+#[kube(group = "clux.dev", version = "v1", kind = "Gateway", plural = "gateways")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct GatewaySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostnames: Option<Vec<String>>,
+    pub labels: BTreeMap<String, String>,
+    pub listeners: Vec<GatewayListeners>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<GatewayResources>,
+    pub selector: GatewaySelector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<GatewayTls>,
+}
+
+static GATEWAY_SPEC_RULE_0: std::sync::LazyLock<Result<cel_interpreter::Program, String>> = std::sync::LazyLock::new(|| cel_interpreter::Program::compile("size(self.listeners) <= 8").map_err(|e| e.to_string()));
+
+impl GatewaySpec {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        validate_cel(&GATEWAY_SPEC_RULE_0, self, path, "size(self.listeners) <= 8", "at most 8 listeners", errors);
+        for (i0, x0) in self.listeners.iter().enumerate() {
+            let path = &format!("{path}.listeners");
+            x0.validate_at(&format!("{path}[{i0}]"), errors);
+        }
+        if let Some(x0) = self.resources.as_ref() {
+            x0.validate_at(&format!("{path}.resources"), errors);
+        }
+        self.selector.validate_at(&format!("{path}.selector"), errors);
+        if let Some(x0) = self.tls.as_ref() {
+            x0.validate_at(&format!("{path}.tls"), errors);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayListeners {
+    pub name: String,
+    pub port: i32,
+}
+
+static GATEWAY_SPEC_LISTENERS_RULE_0: std::sync::LazyLock<Result<cel_interpreter::Program, String>> = std::sync::LazyLock::new(|| cel_interpreter::Program::compile("self.port > 0").map_err(|e| e.to_string()));
+
+impl GatewayListeners {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        validate_cel(&GATEWAY_SPEC_LISTENERS_RULE_0, self, path, "self.port > 0", "port must be positive", errors);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayResources {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<BTreeMap<String, IntOrString>>,
+}
+
+impl GatewayResources {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, _path: &str, _errors: &mut Vec<ValidationError>) {
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewaySelector {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+}
+
+static GATEWAY_SPEC_SELECTOR_RULE_0: std::sync::LazyLock<Result<cel_interpreter::Program, String>> = std::sync::LazyLock::new(|| cel_interpreter::Program::compile("has(self.app)").map_err(|e| e.to_string()));
+
+impl GatewaySelector {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        validate_cel(&GATEWAY_SPEC_SELECTOR_RULE_0, self, path, "has(self.app)", "app must be set", errors);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayTls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+static GATEWAY_SPEC_TLS_RULE_0: std::sync::LazyLock<Result<cel_interpreter::Program, String>> = std::sync::LazyLock::new(|| cel_interpreter::Program::compile("self.mode != ''").map_err(|e| e.to_string()));
+
+impl GatewayTls {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        validate_cel(&GATEWAY_SPEC_TLS_RULE_0, self, path, "self.mode != ''", "failed rule: self.mode != ''", errors);
+    }
+}
+
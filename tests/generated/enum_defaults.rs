@@ -0,0 +1,75 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/enum-crd.yaml -D PartialEq -D Default
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[kube(group = "clux.dev", version = "v1", kind = "Widget", plural = "widgets")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct WidgetSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WidgetMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<WidgetPriority>,
+    pub protocol: WidgetProtocol,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum WidgetMode {
+    #[serde(rename = "read-only")]
+    #[default]
+    ReadOnly,
+    #[serde(rename = "read-write")]
+    ReadWrite,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetPriority {
+    Value1,
+    Value2,
+    Value3,
+}
+
+impl Serialize for WidgetPriority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value1 => serde_json::json!(1).serialize(serializer),
+            Self::Value2 => serde_json::json!(2).serialize(serializer),
+            Self::Value3 => serde_json::json!(3).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetPriority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value == serde_json::json!(1) {
+            return Ok(Self::Value1);
+        }
+        if value == serde_json::json!(2) {
+            return Ok(Self::Value2);
+        }
+        if value == serde_json::json!(3) {
+            return Ok(Self::Value3);
+        }
+        Err(serde::de::Error::custom(format!("unknown variant {value}, expected one of {}", "1, 2, 3")))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum WidgetProtocol {
+    #[serde(rename = "TCP")]
+    Tcp,
+    #[serde(rename = "UDP")]
+    Udp,
+    #[serde(rename = "http/2")]
+    Http2,
+}
+
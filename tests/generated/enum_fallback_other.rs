@@ -0,0 +1,171 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/enum-crd.yaml -A -D PartialEq --enum-fallback other
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use schemars::JsonSchema;
+    pub use serde::{Serialize, Deserialize};
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Widget", plural = "widgets")]
+#[kube(namespaced)]
+pub struct WidgetSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WidgetMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<WidgetPriority>,
+    pub protocol: WidgetProtocol,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WidgetMode {
+    ReadOnly,
+    ReadWrite,
+    Other(String),
+}
+
+impl Serialize for WidgetMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::ReadOnly => serde_json::json!("read-only").serialize(serializer),
+            Self::ReadWrite => serde_json::json!("read-write").serialize(serializer),
+            Self::Other(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value == serde_json::json!("read-only") {
+            return Ok(Self::ReadOnly);
+        }
+        if value == serde_json::json!("read-write") {
+            return Ok(Self::ReadWrite);
+        }
+        serde_json::from_value(value).map(Self::Other).map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for WidgetMode {
+    fn schema_name() -> String {
+        "WidgetMode".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(vec![serde_json::json!("read-only"), serde_json::json!("read-write")]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WidgetPriority {
+    Value1,
+    Value2,
+    Value3,
+    Other(serde_json::Value),
+}
+
+impl Serialize for WidgetPriority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value1 => serde_json::json!(1).serialize(serializer),
+            Self::Value2 => serde_json::json!(2).serialize(serializer),
+            Self::Value3 => serde_json::json!(3).serialize(serializer),
+            Self::Other(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetPriority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value == serde_json::json!(1) {
+            return Ok(Self::Value1);
+        }
+        if value == serde_json::json!(2) {
+            return Ok(Self::Value2);
+        }
+        if value == serde_json::json!(3) {
+            return Ok(Self::Value3);
+        }
+        Ok(Self::Other(value))
+    }
+}
+
+impl JsonSchema for WidgetPriority {
+    fn schema_name() -> String {
+        "WidgetPriority".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::Integer.into()),
+            enum_values: Some(vec![serde_json::json!(1), serde_json::json!(2), serde_json::json!(3)]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WidgetProtocol {
+    Tcp,
+    Udp,
+    Http2,
+    Other(String),
+}
+
+impl Serialize for WidgetProtocol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Tcp => serde_json::json!("TCP").serialize(serializer),
+            Self::Udp => serde_json::json!("UDP").serialize(serializer),
+            Self::Http2 => serde_json::json!("http/2").serialize(serializer),
+            Self::Other(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetProtocol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value == serde_json::json!("TCP") {
+            return Ok(Self::Tcp);
+        }
+        if value == serde_json::json!("UDP") {
+            return Ok(Self::Udp);
+        }
+        if value == serde_json::json!("http/2") {
+            return Ok(Self::Http2);
+        }
+        serde_json::from_value(value).map(Self::Other).map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for WidgetProtocol {
+    fn schema_name() -> String {
+        "WidgetProtocol".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(vec![serde_json::json!("TCP"), serde_json::json!("UDP"), serde_json::json!("http/2")]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
@@ -0,0 +1,100 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/enum-crd.yaml -A -D PartialEq --enum-fallback unknown
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use schemars::JsonSchema;
+    pub use serde::{Serialize, Deserialize};
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Widget", plural = "widgets")]
+#[kube(namespaced)]
+pub struct WidgetSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WidgetMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<WidgetPriority>,
+    pub protocol: WidgetProtocol,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[non_exhaustive]
+pub enum WidgetMode {
+    #[serde(rename = "read-only")]
+    ReadOnly,
+    #[serde(rename = "read-write")]
+    ReadWrite,
+    #[schemars(skip)]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WidgetPriority {
+    Value1,
+    Value2,
+    Value3,
+    Unknown,
+}
+
+impl Serialize for WidgetPriority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value1 => serde_json::json!(1).serialize(serializer),
+            Self::Value2 => serde_json::json!(2).serialize(serializer),
+            Self::Value3 => serde_json::json!(3).serialize(serializer),
+            Self::Unknown => serializer.serialize_str("Unknown"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetPriority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value == serde_json::json!(1) {
+            return Ok(Self::Value1);
+        }
+        if value == serde_json::json!(2) {
+            return Ok(Self::Value2);
+        }
+        if value == serde_json::json!(3) {
+            return Ok(Self::Value3);
+        }
+        Ok(Self::Unknown)
+    }
+}
+
+impl JsonSchema for WidgetPriority {
+    fn schema_name() -> String {
+        "WidgetPriority".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::Integer.into()),
+            enum_values: Some(vec![serde_json::json!(1), serde_json::json!(2), serde_json::json!(3)]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[non_exhaustive]
+pub enum WidgetProtocol {
+    #[serde(rename = "TCP")]
+    Tcp,
+    #[serde(rename = "UDP")]
+    Udp,
+    #[serde(rename = "http/2")]
+    Http2,
+    #[schemars(skip)]
+    #[serde(other)]
+    Unknown,
+}
+
@@ -0,0 +1,140 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/enum-crd.yaml -D PartialEq --enum-impls
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[kube(group = "clux.dev", version = "v1", kind = "Widget", plural = "widgets")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct WidgetSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WidgetMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<WidgetPriority>,
+    pub protocol: WidgetProtocol,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum WidgetMode {
+    #[serde(rename = "read-only")]
+    ReadOnly,
+    #[serde(rename = "read-write")]
+    ReadWrite,
+}
+
+impl WidgetMode {
+    /// All variants of the enum
+    pub const ALL: &'static [Self] = &[Self::ReadOnly, Self::ReadWrite];
+}
+
+impl AsRef<str> for WidgetMode {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::ReadOnly => "read-only",
+            Self::ReadWrite => "read-write",
+        }
+    }
+}
+
+impl std::fmt::Display for WidgetMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl std::str::FromStr for WidgetMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read-only" => Ok(Self::ReadOnly),
+            "read-write" => Ok(Self::ReadWrite),
+            _ => Err(format!("unknown variant {s:?}, expected one of {}", "read-only, read-write")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetPriority {
+    Value1,
+    Value2,
+    Value3,
+}
+
+impl Serialize for WidgetPriority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value1 => serde_json::json!(1).serialize(serializer),
+            Self::Value2 => serde_json::json!(2).serialize(serializer),
+            Self::Value3 => serde_json::json!(3).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetPriority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value == serde_json::json!(1) {
+            return Ok(Self::Value1);
+        }
+        if value == serde_json::json!(2) {
+            return Ok(Self::Value2);
+        }
+        if value == serde_json::json!(3) {
+            return Ok(Self::Value3);
+        }
+        Err(serde::de::Error::custom(format!("unknown variant {value}, expected one of {}", "1, 2, 3")))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum WidgetProtocol {
+    #[serde(rename = "TCP")]
+    Tcp,
+    #[serde(rename = "UDP")]
+    Udp,
+    #[serde(rename = "http/2")]
+    Http2,
+}
+
+impl WidgetProtocol {
+    /// All variants of the enum
+    pub const ALL: &'static [Self] = &[Self::Tcp, Self::Udp, Self::Http2];
+}
+
+impl AsRef<str> for WidgetProtocol {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Tcp => "TCP",
+            Self::Udp => "UDP",
+            Self::Http2 => "http/2",
+        }
+    }
+}
+
+impl std::fmt::Display for WidgetProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl std::str::FromStr for WidgetProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TCP" => Ok(Self::Tcp),
+            "UDP" => Ok(Self::Udp),
+            "http/2" => Ok(Self::Http2),
+            _ => Err(format!("unknown variant {s:?}, expected one of {}", "TCP, UDP, http/2")),
+        }
+    }
+}
+
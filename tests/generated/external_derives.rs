@@ -0,0 +1,47 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/validation-crd.yaml -D PartialEq -D Eq -D Hash --use spec.selector=k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+    pub use std::collections::BTreeMap;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug)]
+#[kube(group = "clux.dev", version = "v1", kind = "Gateway", plural = "gateways")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct GatewaySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostnames: Option<Vec<String>>,
+    pub labels: BTreeMap<String, String>,
+    pub listeners: Vec<GatewayListeners>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<GatewayResources>,
+    pub selector: k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<GatewayTls>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GatewayListeners {
+    pub name: String,
+    pub port: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GatewayResources {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<BTreeMap<String, IntOrString>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GatewayTls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
@@ -0,0 +1,186 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/validation-crd.yaml --constraints generated
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+    pub use std::collections::BTreeMap;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+}
+use self::prelude::*;
+
+/// A validation rule from the schema that did not hold
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    /// Path to the field the rule applies to
+    pub field: String,
+    /// The CEL rule or schema constraint that failed
+    pub rule: String,
+    /// The message of the rule, or the reason it could not be evaluated
+    pub message: String,
+}
+
+fn validate_constraint(holds: bool, field: &str, rule: &str, message: &str, errors: &mut Vec<ValidationError>) {
+    if !holds {
+        errors.push(ValidationError {
+            field: field.to_string(),
+            rule: rule.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+fn all_unique<T: Serialize>(values: &[T]) -> bool {
+    let values: Vec<_> = values.iter().map(|v| serde_json::to_value(v).ok()).collect();
+    values.iter().enumerate().all(|(i, v)| !values[..i].contains(v))
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug)]
+#[kube(group = "clux.dev", version = "v1", kind = "Gateway", plural = "gateways")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct GatewaySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostnames: Option<Vec<String>>,
+    pub labels: BTreeMap<String, String>,
+    pub listeners: Vec<GatewayListeners>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<GatewayResources>,
+    pub selector: GatewaySelector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<GatewayTls>,
+}
+
+impl GatewaySpec {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        if let Some(value) = &self.hostnames {
+            validate_constraint(all_unique(value), &format!("{path}.hostnames"), "uniqueItems: true", "must only have unique items", errors);
+        }
+        validate_constraint(self.labels.len() <= 16, &format!("{path}.labels"), "maxProperties: 16", "must have at most 16 properties", errors);
+        validate_constraint(self.labels.contains_key("app"), &format!("{path}.labels"), "required: app", "must have the property app", errors);
+        validate_constraint(self.listeners.len() <= 8, &format!("{path}.listeners"), "maxItems: 8", "must have at most 8 items", errors);
+        for (i0, x0) in self.listeners.iter().enumerate() {
+            let path = &format!("{path}.listeners");
+            x0.validate_at(&format!("{path}[{i0}]"), errors);
+        }
+        if let Some(x0) = self.resources.as_ref() {
+            x0.validate_at(&format!("{path}.resources"), errors);
+        }
+        self.selector.validate_at(&format!("{path}.selector"), errors);
+        if let Some(x0) = self.tls.as_ref() {
+            x0.validate_at(&format!("{path}.tls"), errors);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayListeners {
+    pub name: String,
+    pub port: i32,
+}
+
+static GATEWAY_SPEC_LISTENERS_NAME_PATTERN: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| regex::Regex::new(r"^[a-z]+$").unwrap());
+
+impl GatewayListeners {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        validate_constraint(GATEWAY_SPEC_LISTENERS_NAME_PATTERN.is_match(&self.name), &format!("{path}.name"), "pattern: ^[a-z]+$", "must match the pattern ^[a-z]+$", errors);
+        validate_constraint(self.name.chars().count() >= 1, &format!("{path}.name"), "minLength: 1", "must be at least 1 characters long", errors);
+        validate_constraint(self.name.chars().count() <= 15, &format!("{path}.name"), "maxLength: 15", "must be at most 15 characters long", errors);
+        validate_constraint(self.port as f64 >= 1.0, &format!("{path}.port"), "minimum: 1", "must be >= 1", errors);
+        validate_constraint(self.port as f64 <= 65535.0, &format!("{path}.port"), "maximum: 65535", "must be <= 65535", errors);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayResources {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<BTreeMap<String, IntOrString>>,
+}
+
+impl GatewayResources {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, _path: &str, _errors: &mut Vec<ValidationError>) {
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewaySelector {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+}
+
+impl GatewaySelector {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        if let Some(value) = &self.app {
+            validate_constraint(value.chars().count() >= 1, &format!("{path}.app"), "minLength: 1", "must be at least 1 characters long", errors);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayTls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+impl GatewayTls {
+    /// Evaluate the validation rules from the schema
+    pub fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_at(&self, _path: &str, _errors: &mut Vec<ValidationError>) {
+    }
+}
+
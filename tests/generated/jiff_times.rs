@@ -0,0 +1,72 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/format-crd.yaml -A --time-type Jiff -D PartialEq -D Default
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use schemars::JsonSchema;
+    pub use serde::{Serialize, Deserialize};
+    pub use jiff::Timestamp;
+    pub use jiff::civil::Date;
+}
+use self::prelude::*;
+
+/// Schema of Timestamp, which has no JsonSchema impl
+enum TimestampSchema {}
+
+impl JsonSchema for TimestampSchema {
+    fn schema_name() -> String {
+        "TimestampSchema".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("date-time".to_string());
+        schema.into()
+    }
+}
+
+/// Schema of Date, which has no JsonSchema impl
+enum DateSchema {}
+
+impl JsonSchema for DateSchema {
+    fn schema_name() -> String {
+        "DateSchema".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("date".to_string());
+        schema.into()
+    }
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Mailer", plural = "mailers")]
+#[kube(namespaced)]
+pub struct MailerSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<String>,
+    pub from: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<DateSchema>>")]
+    pub holidays: Option<Vec<Date>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    pub relay: String,
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "sendAfter")]
+    #[schemars(with = "Option<TimestampSchema>")]
+    pub send_after: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
@@ -0,0 +1,108 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/validation-crd.yaml --pattern-types -D PartialEq -D Default
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+    pub use std::collections::BTreeMap;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+}
+use self::prelude::*;
+
+/// A string matching the pattern of the GatewayListenersName property
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GatewayListenersName(String);
+
+impl GatewayListenersName {
+    pub const PATTERN: &'static str = r"^[a-z]+$";
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for GatewayListenersName {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        static PATTERN: std::sync::LazyLock<regex::Regex> =
+            std::sync::LazyLock::new(|| regex::Regex::new(GatewayListenersName::PATTERN).unwrap());
+        if PATTERN.is_match(&value) {
+            Ok(Self(value))
+        } else {
+            Err(format!("{value:?} does not match the pattern {}", Self::PATTERN))
+        }
+    }
+}
+
+impl std::str::FromStr for GatewayListenersName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl std::ops::Deref for GatewayListenersName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for GatewayListenersName {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for GatewayListenersName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::try_from(s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[kube(group = "clux.dev", version = "v1", kind = "Gateway", plural = "gateways")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct GatewaySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostnames: Option<Vec<String>>,
+    pub labels: BTreeMap<String, String>,
+    pub listeners: Vec<GatewayListeners>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<GatewayResources>,
+    pub selector: GatewaySelector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<GatewayTls>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GatewayListeners {
+    pub name: GatewayListenersName,
+    pub port: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct GatewayResources {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<BTreeMap<String, IntOrString>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct GatewaySelector {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct GatewayTls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
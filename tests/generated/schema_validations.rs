@@ -0,0 +1,166 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/validation-crd.yaml -A
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use schemars::JsonSchema;
+    pub use serde::{Serialize, Deserialize};
+    pub use std::collections::BTreeMap;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug)]
+#[kube(group = "clux.dev", version = "v1", kind = "Gateway", plural = "gateways")]
+#[kube(namespaced)]
+pub struct GatewaySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostnames: Option<Vec<String>>,
+    pub labels: BTreeMap<String, String>,
+    pub listeners: Vec<GatewayListeners>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<GatewayResources>,
+    pub selector: GatewaySelector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<GatewayTls>,
+}
+
+fn gateway_spec_selector_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = <GatewaySelector as JsonSchema>::json_schema(gen).into_object();
+    schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!([{"message":"app must be set","rule":"has(self.app)"}]));
+    schema.into()
+}
+
+fn gateway_spec_tls_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = <GatewayTls as JsonSchema>::json_schema(gen).into_object();
+    schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!([{"rule":"self.mode != ''"}]));
+    schema.into()
+}
+
+mod gateway_spec_schema {
+    use super::*;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    pub struct GatewaySpec {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub hostnames: Option<Vec<String>>,
+        pub labels: BTreeMap<String, String>,
+        pub listeners: Vec<GatewayListeners>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub resources: Option<GatewayResources>,
+        #[schemars(schema_with = "gateway_spec_selector_schema")]
+        pub selector: GatewaySelector,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(schema_with = "gateway_spec_tls_schema")]
+        pub tls: Option<GatewayTls>,
+    }
+}
+
+impl JsonSchema for GatewaySpec {
+    fn schema_name() -> String {
+        "GatewaySpec".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <gateway_spec_schema::GatewaySpec as JsonSchema>::json_schema(gen).into_object();
+        schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!([{"message":"at most 8 listeners","rule":"size(self.listeners) <= 8"}]));
+        schema.into()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayListeners {
+    pub name: String,
+    pub port: i32,
+}
+
+mod gateway_listeners_schema {
+    use super::*;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    pub struct GatewayListeners {
+        pub name: String,
+        pub port: i32,
+    }
+}
+
+impl JsonSchema for GatewayListeners {
+    fn schema_name() -> String {
+        "GatewayListeners".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <gateway_listeners_schema::GatewayListeners as JsonSchema>::json_schema(gen).into_object();
+        schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!([{"message":"port must be positive","rule":"self.port > 0"}]));
+        schema.into()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct GatewayResources {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<BTreeMap<String, IntOrString>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewaySelector {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+}
+
+mod gateway_selector_schema {
+    use super::*;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    pub struct GatewaySelector {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub app: Option<String>,
+    }
+}
+
+impl JsonSchema for GatewaySelector {
+    fn schema_name() -> String {
+        "GatewaySelector".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <gateway_selector_schema::GatewaySelector as JsonSchema>::json_schema(gen).into_object();
+        schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!([{"message":"app must be set","rule":"has(self.app)"}]));
+        schema.into()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GatewayTls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+mod gateway_tls_schema {
+    use super::*;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    pub struct GatewayTls {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub mode: Option<String>,
+    }
+}
+
+impl JsonSchema for GatewayTls {
+    fn schema_name() -> String {
+        "GatewayTls".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <gateway_tls_schema::GatewayTls as JsonSchema>::json_schema(gen).into_object();
+        schema.extensions.insert("x-kubernetes-validations".to_string(), serde_json::json!([{"rule":"self.mode != ''"}]));
+        schema.into()
+    }
+}
+
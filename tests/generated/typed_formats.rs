@@ -0,0 +1,370 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/format-crd.yaml -A --typed-durations --typed-formats --secrets --byte-strings -D PartialEq -D Default
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use schemars::JsonSchema;
+    pub use serde::{Serialize, Deserialize};
+    pub use chrono::{DateTime, Utc};
+    pub use chrono::naive::NaiveDate;
+    pub use k8s_openapi::ByteString;
+}
+use self::prelude::*;
+
+/// A duration in the go format, such as "1h30m", "2.5s" or "250ms"
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GoDuration(pub std::time::Duration);
+
+impl From<std::time::Duration> for GoDuration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<GoDuration> for std::time::Duration {
+    fn from(duration: GoDuration) -> Self {
+        duration.0
+    }
+}
+
+impl std::str::FromStr for GoDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid duration {s:?}");
+        let mut rest = s.strip_prefix('+').unwrap_or(s);
+        if rest == "0" {
+            return Ok(Self::default());
+        }
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+        let mut nanos: u128 = 0;
+        while !rest.is_empty() {
+            let split = rest.find(|c: char| !c.is_ascii_digit() && c != '.').ok_or_else(invalid)?;
+            let (number, tail) = rest.split_at(split);
+            let split = tail.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(split);
+            let scale: u128 = match unit {
+                "ns" => 1,
+                "us" | "µs" | "μs" => 1_000,
+                "ms" => 1_000_000,
+                "s" => 1_000_000_000,
+                "m" => 60_000_000_000,
+                "h" => 3_600_000_000_000,
+                "d" => 86_400_000_000_000,
+                "w" => 604_800_000_000_000,
+                "y" => 31_536_000_000_000_000,
+                _ => return Err(invalid()),
+            };
+            let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+            if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+                return Err(invalid());
+            }
+            let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+            let mut value = whole.checked_mul(scale).ok_or_else(invalid)?;
+            let mut divisor = 1;
+            for digit in fraction.bytes().take(18) {
+                divisor *= 10;
+                value += u128::from(digit - b'0') * scale / divisor;
+            }
+            nanos = nanos.checked_add(value).ok_or_else(invalid)?;
+            rest = tail;
+        }
+        let nanos = u64::try_from(nanos).map_err(|_| invalid())?;
+        Ok(Self(std::time::Duration::from_nanos(nanos)))
+    }
+}
+
+impl std::fmt::Display for GoDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn decimal(value: u128, scale: u128) -> String {
+            let (whole, fraction) = (value / scale, value % scale);
+            if fraction == 0 {
+                return whole.to_string();
+            }
+            let width = scale.ilog10() as usize;
+            format!("{whole}.{fraction:0width$}").trim_end_matches('0').to_string()
+        }
+        let nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+        if nanos < 1_000 {
+            return write!(f, "{nanos}ns");
+        }
+        if nanos < 1_000_000 {
+            return write!(f, "{}µs", decimal(nanos, 1_000));
+        }
+        if nanos < 1_000_000_000 {
+            return write!(f, "{}ms", decimal(nanos, 1_000_000));
+        }
+        let hours = nanos / 3_600_000_000_000;
+        let minutes = nanos / 60_000_000_000 % 60;
+        if hours > 0 {
+            write!(f, "{hours}h")?;
+        }
+        if hours > 0 || minutes > 0 {
+            write!(f, "{minutes}m")?;
+        }
+        write!(f, "{}s", decimal(nanos % 60_000_000_000, 1_000_000_000))
+    }
+}
+
+impl Serialize for GoDuration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GoDuration {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for GoDuration {
+    fn schema_name() -> String {
+        "GoDuration".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        <String as JsonSchema>::json_schema(gen)
+    }
+}
+
+fn is_hostname(s: &str) -> bool {
+    s.len() <= 253
+        && s.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+fn is_email(s: &str) -> bool {
+    s.rsplit_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty()
+            && local.len() <= 64
+            && !local.contains(|c: char| c.is_whitespace() || c == '@')
+            && is_hostname(domain)
+    })
+}
+
+/// A string with the email format
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Email(String);
+
+impl Email {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Email {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Email {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Email {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_email(s) {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(format!("invalid email {s:?}"))
+        }
+    }
+}
+
+impl Serialize for Email {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Email {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for Email {
+    fn schema_name() -> String {
+        "Email".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("email".to_string());
+        schema.into()
+    }
+}
+
+/// A string with the hostname format
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hostname(String);
+
+impl Hostname {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Hostname {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Hostname {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Hostname {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_hostname(s) {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(format!("invalid hostname {s:?}"))
+        }
+    }
+}
+
+impl Serialize for Hostname {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hostname {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for Hostname {
+    fn schema_name() -> String {
+        "Hostname".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("hostname".to_string());
+        schema.into()
+    }
+}
+
+/// Schema of ByteString, which has no JsonSchema impl
+enum ByteStringSchema {}
+
+impl JsonSchema for ByteStringSchema {
+    fn schema_name() -> String {
+        "ByteStringSchema".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("byte".to_string());
+        schema.into()
+    }
+}
+
+/// A secret string that is redacted from Debug output
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RedactedString(String);
+
+impl RedactedString {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// The secret value
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for RedactedString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl std::fmt::Debug for RedactedString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl JsonSchema for RedactedString {
+    fn schema_name() -> String {
+        "RedactedString".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = <String as JsonSchema>::json_schema(gen).into_object();
+        schema.format = Some("password".to_string());
+        schema.into()
+    }
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Mailer", plural = "mailers")]
+#[kube(namespaced)]
+pub struct MailerSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<ByteStringSchema>")]
+    pub certificate: Option<ByteString>,
+    pub from: Email,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holidays: Option<Vec<NaiveDate>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<RedactedString>,
+    pub relay: Hostname,
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "sendAfter")]
+    pub send_after: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<GoDuration>,
+}
+
@@ -0,0 +1,64 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium command: kopium -f tests/validation-crd.yaml --constraints validator
+// kopium version: 0.18.0
+
+#[allow(unused_imports)]
+mod prelude {
+    pub use kube::CustomResource;
+    pub use serde::{Serialize, Deserialize};
+    pub use std::collections::BTreeMap;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+    pub use validator::Validate;
+}
+use self::prelude::*;
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, validator::Validate)]
+#[kube(group = "clux.dev", version = "v1", kind = "Gateway", plural = "gateways")]
+#[kube(namespaced)]
+#[kube(schema = "disabled")]
+pub struct GatewaySpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostnames: Option<Vec<String>>,
+    #[validate(length(max = 16))]
+    pub labels: BTreeMap<String, String>,
+    #[validate(length(max = 8), nested)]
+    pub listeners: Vec<GatewayListeners>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub resources: Option<GatewayResources>,
+    #[validate(nested)]
+    pub selector: GatewaySelector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub tls: Option<GatewayTls>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, validator::Validate)]
+pub struct GatewayListeners {
+    #[validate(regex(path = *GATEWAY_SPEC_LISTENERS_NAME_PATTERN), length(min = 1, max = 15))]
+    pub name: String,
+    #[validate(range(min = 1, max = 65535))]
+    pub port: i32,
+}
+
+static GATEWAY_SPEC_LISTENERS_NAME_PATTERN: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| regex::Regex::new(r"^[a-z]+$").unwrap());
+
+#[derive(Serialize, Deserialize, Clone, Debug, validator::Validate)]
+pub struct GatewayResources {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<BTreeMap<String, IntOrString>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, validator::Validate)]
+pub struct GatewaySelector {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub app: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, validator::Validate)]
+pub struct GatewayTls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+